@@ -2,10 +2,17 @@
 use anyhow::Result;
 use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response};
 
+use cw2::set_contract_version;
+
 use minter_export::contract::{
-    execute as minter_execute, instantiate as minter_instantiate, query as minter_query,
+    execute as minter_execute, instantiate as minter_instantiate, migrate as minter_migrate,
+    query as minter_query,
 };
-use minter_export::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use minter_export::error::ContractError;
+use minter_export::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // This is a simple type to let us handle empty extensions
 pub type Extension = Option<Empty>;
@@ -17,6 +24,7 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     minter_instantiate(deps, env, info, msg)
 }
 
@@ -31,26 +39,40 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg<Extension>) -> Result<Binary> {
     minter_query(deps, env, msg)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    minter_migrate(deps, env, msg, CONTRACT_NAME, CONTRACT_VERSION)
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, Uint128};
+    use cosmwasm_std::{attr, coins, from_binary, Addr, Uint128, Uint64};
+    use cw2::get_contract_version;
     use cw721_base::MintMsg;
-    use minter_export::msg::MintRequest;
+    use minter_export::msg::{ContractInfoResponse, Expiration, MintRequest, MintSignature};
+    use minter_export::state::{
+        ContractInfo, FeeShare, FeeSplit, KeyScheme, LegacyContractInfo, MinterKey, CONTRACT_INFO,
+        LEGACY_CONTRACT_INFO, MINTER_KEYS,
+    };
     fn init_helper(deps: DepsMut) -> Response {
         let instantiate_msg = InstantiateMsg {
             name: "fee_contract".to_string(),
             owner: None,
-            treasury: Some("meonly".to_string()),
-            fee_price: Uint128::from(456u128),
-            project_price: Uint128::from(0u128),
-            project_treasury: "meonly".to_string(),
+            prices: coins(456, "uluna"),
+            fee_shares: vec![FeeShare {
+                recipient: "meonly".to_string(),
+                bps: 10000,
+            }],
+            dust_recipient: None,
             minter: "AjBui2DTkLVKo2p5uCXf68SbHzSDoRDESKNtsr3+vtJI".to_string(),
+            minter_scheme: None,
+            threshold: None,
         };
         let info = mock_info("creator", &[]);
         let env = mock_env();
@@ -65,6 +87,37 @@ pub mod tests {
         assert_eq!(0, res.messages.len());
     }
 
+    #[test]
+    fn test_contract_info() {
+        let mut deps = mock_dependencies();
+        init_helper(deps.as_mut());
+
+        let res: ContractInfoResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {}).unwrap())
+                .unwrap();
+        assert_eq!(
+            res,
+            ContractInfoResponse {
+                name: "fee_contract".to_string(),
+                owner: "creator".to_string(),
+                nft_contract: None,
+                minter: Some("AjBui2DTkLVKo2p5uCXf68SbHzSDoRDESKNtsr3+vtJI".to_string()),
+                threshold: 1,
+                prices: coins(456, "uluna"),
+                cw20_prices: vec![],
+                price_schedule: vec![],
+                fee_split: FeeSplit {
+                    shares: vec![FeeShare {
+                        recipient: "meonly".to_string(),
+                        bps: 10000,
+                    }],
+                    dust_recipient: "creator".to_string(),
+                },
+                merkle_root: None,
+            }
+        );
+    }
+
     #[test]
     fn test_update_owner() {
         let mut deps = mock_dependencies();
@@ -94,32 +147,30 @@ pub mod tests {
     }
 
     #[test]
-    fn test_update_fee_price() {
+    fn test_update_fee_split() {
         let mut deps = mock_dependencies();
         init_helper(deps.as_mut());
 
         let info = mock_info("creator", &[]);
         let env = mock_env();
-        execute(
-            deps.as_mut(),
-            env.clone(),
-            info,
-            ExecuteMsg::SetFeePrice {
-                price: Uint128::from(899898u128),
-            },
-        )
-        .unwrap();
+        let fee_split = ExecuteMsg::SetFeeSplit {
+            prices: coins(899898, "uluna"),
+            shares: vec![
+                FeeShare {
+                    recipient: "artist".to_string(),
+                    bps: 5000,
+                },
+                FeeShare {
+                    recipient: "meonly".to_string(),
+                    bps: 5000,
+                },
+            ],
+            dust_recipient: "meonly".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info, fee_split.clone()).unwrap();
 
         let info = mock_info("bad_person", &[]);
-        execute(
-            deps.as_mut(),
-            env,
-            info,
-            ExecuteMsg::SetFeePrice {
-                price: Uint128::from(909009898u128),
-            },
-        )
-        .unwrap_err();
+        execute(deps.as_mut(), env, info, fee_split).unwrap_err();
     }
 
     #[test]
@@ -134,7 +185,8 @@ pub mod tests {
             env.clone(),
             info,
             ExecuteMsg::SetMinter {
-                minter: "any_pu_bkey".to_string(),
+                minter: "A8Bd8ZrrMkJNrJlnb29x5vVeYnsPYAlEsTNYwvf0gtHg".to_string(),
+                scheme: None,
             },
         )
         .unwrap();
@@ -145,7 +197,8 @@ pub mod tests {
             env,
             info,
             ExecuteMsg::SetMinter {
-                minter: "any_pu_bkey".to_string(),
+                minter: "A8Bd8ZrrMkJNrJlnb29x5vVeYnsPYAlEsTNYwvf0gtHg".to_string(),
+                scheme: None,
             },
         )
         .unwrap_err();
@@ -187,8 +240,14 @@ pub mod tests {
                 mint_request: MintRequest {
                     mint_msg: mint_msg.clone(),
                     nft_contract: nft_contract.clone(),
+                    nonce: Uint64::new(1),
+                    expires_at: Expiration::AtHeight(1_000_000),
                 },
-                signature: signature.to_string(),
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: signature.to_string(),
+                    adr036_signer: None,
+                }],
             },
         )
         .unwrap_err();
@@ -202,10 +261,103 @@ pub mod tests {
                 mint_request: MintRequest {
                     mint_msg,
                     nft_contract,
+                    nonce: Uint64::new(1),
+                    expires_at: Expiration::AtHeight(1_000_000),
                 },
-                signature: signature.to_string(),
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: signature.to_string(),
+                    adr036_signer: None,
+                }],
             },
         )
         .unwrap_err();
     }
+
+    #[test]
+    fn test_migrate() {
+        let mut deps = mock_dependencies();
+
+        // Minter deployed before the contracts were versioned
+        let legacy_info = LegacyContractInfo {
+            name: "fee_contract".to_string(),
+            owner: Addr::unchecked("creator"),
+            nft_contract: Some("nft".to_string()),
+            minter: "Atxyc0QMQkWOR0WfxpDKIhPpQInx34G9DtM7EWUHTWoj".to_string(),
+            fee_price: Uint128::from(190u128),
+            treasury: Addr::unchecked("meonly"),
+            project_price: Uint128::from(253u128),
+            project_treasury: Addr::unchecked("project"),
+        };
+        LEGACY_CONTRACT_INFO
+            .save(deps.as_mut().storage, &legacy_info)
+            .unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[1], attr("from_version", "legacy"));
+        assert_eq!(
+            CONTRACT_INFO.load(deps.as_ref().storage).unwrap(),
+            ContractInfo {
+                name: "fee_contract".to_string(),
+                owner: Addr::unchecked("creator"),
+                nft_contract: Some("nft".to_string()),
+                threshold: 1,
+                // The prices become shares of the total price
+                prices: coins(443, "uluna"),
+                cw20_prices: vec![],
+                price_schedule: vec![],
+                fee_split: FeeSplit {
+                    shares: vec![
+                        FeeShare {
+                            recipient: "meonly".to_string(),
+                            bps: 4288,
+                        },
+                        FeeShare {
+                            recipient: "project".to_string(),
+                            bps: 5712,
+                        }
+                    ],
+                    dust_recipient: "meonly".to_string(),
+                },
+            }
+        );
+        // The minter public key becomes the default key of the keyring
+        assert_eq!(
+            MINTER_KEYS.load(deps.as_ref().storage, "default").unwrap(),
+            MinterKey {
+                key_id: "default".to_string(),
+                pubkey: "Atxyc0QMQkWOR0WfxpDKIhPpQInx34G9DtM7EWUHTWoj".to_string(),
+                scheme: KeyScheme::Secp256k1,
+                valid_from: None,
+                valid_until: None,
+            }
+        );
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.contract, CONTRACT_NAME.to_string());
+        assert_eq!(version.version, CONTRACT_VERSION.to_string());
+
+        // Migrating to the same version is allowed
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[1], attr("from_version", CONTRACT_VERSION));
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::CannotDowngrade {
+                stored: "99.0.0".to_string(),
+                current: CONTRACT_VERSION.to_string(),
+            }
+        );
+
+        set_contract_version(deps.as_mut().storage, "crates.io:minter-metadata", "0.9.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::WrongContract {
+                expected: CONTRACT_NAME.to_string(),
+                found: "crates.io:minter-metadata".to_string(),
+            }
+        );
+    }
 }
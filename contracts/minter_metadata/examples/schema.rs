@@ -1,4 +1,4 @@
-use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
 use cw721_metadata_onchain::Metadata;
 use std::env::current_dir;
 use std::fs::create_dir_all;
@@ -6,7 +6,10 @@ use std::fs::create_dir_all;
 // This is a simple type to let us handle empty extensions
 pub type Extension = Option<Metadata>;
 
-use minter_export::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use minter_export::msg::{
+    ContractInfoResponse, ExecuteMsg, FeeResponse, InstantiateMsg, MerkleRootResponse, MigrateMsg,
+    MinterKeysResponse, QueryMsg, ReceiveMsg, VerifyMintRequestResponse,
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -15,6 +18,13 @@ fn main() {
     remove_schemas(&out_dir).unwrap();
 
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
-    export_schema(&schema_for!(ExecuteMsg<Extension>), &out_dir);
-    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema_with_title(&schema_for!(ExecuteMsg<Extension>), &out_dir, "ExecuteMsg");
+    export_schema_with_title(&schema_for!(QueryMsg<Extension>), &out_dir, "QueryMsg");
+    export_schema_with_title(&schema_for!(ReceiveMsg<Extension>), &out_dir, "ReceiveMsg");
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(ContractInfoResponse), &out_dir);
+    export_schema(&schema_for!(FeeResponse), &out_dir);
+    export_schema(&schema_for!(MinterKeysResponse), &out_dir);
+    export_schema(&schema_for!(MerkleRootResponse), &out_dir);
+    export_schema(&schema_for!(VerifyMintRequestResponse), &out_dir);
 }
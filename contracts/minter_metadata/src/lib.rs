@@ -4,10 +4,17 @@ use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Respons
 
 use cw721_metadata_onchain::Metadata;
 
+use cw2::set_contract_version;
+
 use minter_export::contract::{
-    execute as minter_execute, instantiate as minter_instantiate, query as minter_query,
+    execute as minter_execute, instantiate as minter_instantiate, migrate as minter_migrate,
+    query as minter_query,
 };
-use minter_export::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use minter_export::error::ContractError;
+use minter_export::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // This is a simple type to let us handle empty extensions
 pub type Extension = Option<Metadata>;
@@ -19,6 +26,7 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     minter_instantiate(deps, env, info, msg)
 }
 
@@ -33,26 +41,75 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg<Extension>) -> Result<Binary> {
     minter_query(deps, env, msg)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    minter_migrate(deps, env, msg, CONTRACT_NAME, CONTRACT_VERSION)
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, Uint128};
+    use cosmwasm_std::{
+        attr, coin, coins, from_binary, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg,
+        Timestamp, Uint128, Uint64, WasmMsg,
+    };
+    use cw20::{Cw20Coin, Cw20ReceiveMsg};
+    use cw20_base::msg::ExecuteMsg as Cw20ExecuteMsg;
     use cw721_base::MintMsg;
-    use minter_export::msg::MintRequest;
+    use k256::ecdsa::signature::Signer;
+    use k256::ecdsa::{Signature, SigningKey};
+    use minter_export::contract::request_hash;
+    use minter_export::error::ContractError;
+    use minter_export::merkle::merkle_leaf;
+    use minter_export::msg::{
+        Expiration, FeeResponse, MerkleRootResponse, MintCheck, MintRequest, MintSignature,
+        MinterKeysResponse, ReceiveMsg, VerifyMintRequestResponse,
+    };
+    use minter_export::signing::signing_bytes;
+    use minter_export::state::{FeeShare, FeeSplit, KeyScheme, MinterKey, PriceTier};
+    use sha2::{Digest, Sha256};
+
+    // Private key of the authority that signs the mint requests in the tests
+    fn authority_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32]).unwrap()
+    }
+
+    fn authority_pubkey() -> String {
+        base64::encode(authority_key().verifying_key().to_bytes())
+    }
+
+    fn sign_helper(env: &Env, mint_request: &MintRequest<Extension>) -> String {
+        sign_with(&authority_key(), env, mint_request)
+    }
+
+    fn sign_with(key: &SigningKey, env: &Env, mint_request: &MintRequest<Extension>) -> String {
+        let bytes = signing_bytes(
+            &env.block.chain_id,
+            env.contract.address.as_str(),
+            mint_request,
+        )
+        .unwrap();
+        let signature: Signature = key.sign(&bytes);
+        base64::encode(signature.as_ref())
+    }
     fn init_helper(deps: DepsMut) -> Response {
         let instantiate_msg = InstantiateMsg {
             name: "fee_contract".to_string(),
             owner: None,
-            treasury: Some("meonly".to_string()),
-            fee_price: Uint128::from(456u128),
-            project_price: Uint128::from(0u128),
-            project_treasury: "meonly".to_string(),
-            minter: "Atxyc0QMQkWOR0WfxpDKIhPpQInx34G9DtM7EWUHTWoj".to_string(),
+            prices: coins(456, "uluna"),
+            fee_shares: vec![FeeShare {
+                recipient: "meonly".to_string(),
+                bps: 10000,
+            }],
+            dust_recipient: None,
+            minter: authority_pubkey(),
+            minter_scheme: None,
+            threshold: None,
         };
         let info = mock_info("creator", &[]);
         let env = mock_env();
@@ -96,32 +153,30 @@ pub mod tests {
     }
 
     #[test]
-    fn test_update_fee_price() {
+    fn test_update_fee_split() {
         let mut deps = mock_dependencies();
         init_helper(deps.as_mut());
 
         let info = mock_info("creator", &[]);
         let env = mock_env();
-        execute(
-            deps.as_mut(),
-            env.clone(),
-            info,
-            ExecuteMsg::SetFeePrice {
-                price: Uint128::from(899898u128),
-            },
-        )
-        .unwrap();
+        let fee_split = ExecuteMsg::SetFeeSplit {
+            prices: coins(899898, "uluna"),
+            shares: vec![
+                FeeShare {
+                    recipient: "artist".to_string(),
+                    bps: 5000,
+                },
+                FeeShare {
+                    recipient: "meonly".to_string(),
+                    bps: 5000,
+                },
+            ],
+            dust_recipient: "meonly".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info, fee_split.clone()).unwrap();
 
         let info = mock_info("bad_person", &[]);
-        execute(
-            deps.as_mut(),
-            env,
-            info,
-            ExecuteMsg::SetFeePrice {
-                price: Uint128::from(909009898u128),
-            },
-        )
-        .unwrap_err();
+        execute(deps.as_mut(), env, info, fee_split).unwrap_err();
     }
 
     #[test]
@@ -136,7 +191,8 @@ pub mod tests {
             env.clone(),
             info,
             ExecuteMsg::SetMinter {
-                minter: "any_pu_bkey".to_string(),
+                minter: "A8Bd8ZrrMkJNrJlnb29x5vVeYnsPYAlEsTNYwvf0gtHg".to_string(),
+                scheme: None,
             },
         )
         .unwrap();
@@ -147,7 +203,8 @@ pub mod tests {
             env,
             info,
             ExecuteMsg::SetMinter {
-                minter: "any_pu_bkey".to_string(),
+                minter: "A8Bd8ZrrMkJNrJlnb29x5vVeYnsPYAlEsTNYwvf0gtHg".to_string(),
+                scheme: None,
             },
         )
         .unwrap_err();
@@ -158,7 +215,7 @@ pub mod tests {
         let mut deps = mock_dependencies();
         init_helper(deps.as_mut());
 
-        let info = mock_info("creator", &coins(77487, "uluna"));
+        let info = mock_info("creator", &coins(45, "uluna"));
         let env = mock_env();
 
         // 1. We initialized the contract with the nft address
@@ -190,34 +247,1645 @@ pub mod tests {
                 youtube_url: None
             })
         };
-        let signature = "jt5iZxgHFg1a0KgI6te3/V2Pz7clGCXGJ1iNqiJG2tsq/jr0HsK7wU3u3UDSErTaAlrfrsT+a5W4F32BJda6Yg==";
+        let mint_request = MintRequest {
+            mint_msg,
+            nft_contract,
+            nonce: Uint64::new(1),
+            expires_at: Expiration::AtHeight(env.block.height + 100),
+        };
+        let signature = sign_helper(&env, &mint_request);
         execute(
             deps.as_mut(),
             env.clone(),
             info,
             ExecuteMsg::Mint {
-                mint_request: MintRequest {
-                    mint_msg: mint_msg.clone(),
-                    nft_contract: nft_contract.clone(),
-                },
-                signature: signature.to_string(),
+                mint_request: mint_request.clone(),
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: signature.clone(),
+                    adr036_signer: None,
+                }],
             },
         )
         .unwrap_err();
 
         let info = mock_info("creator", &coins(456, "uluna"));
         execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: signature.clone(),
+                    adr036_signer: None,
+                }],
+            },
+        )
+        .unwrap();
+
+        // 2. A mint request can only be used once
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature,
+                    adr036_signer: None,
+                }],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::MintRequestAlreadyUsed {}
+        );
+
+        // 3. And only until it expires
+        let expired_request = MintRequest {
+            nonce: Uint64::new(2),
+            expires_at: Expiration::AtTime(env.block.time),
+            ..mint_request.clone()
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: sign_helper(&env, &expired_request),
+                    adr036_signer: None,
+                }],
+                mint_request: expired_request,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::MintRequestExpired {}
+        );
+
+        // It has to expire at some point
+        let endless_request = MintRequest {
+            nonce: Uint64::new(2),
+            expires_at: Expiration::Never {},
+            ..mint_request.clone()
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: sign_helper(&env, &endless_request),
+                    adr036_signer: None,
+                }],
+                mint_request: endless_request,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::MintRequestNeverExpires {}
+        );
+
+        // 4. The request has to target the nft contract of the minter
+        let other_nft_request = MintRequest {
+            nonce: Uint64::new(3),
+            nft_contract: "other_nft".to_string(),
+            ..mint_request.clone()
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: sign_helper(&env, &other_nft_request),
+                    adr036_signer: None,
+                }],
+                mint_request: other_nft_request,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::NftContractMismatch {
+                expected: "terra1sf2fftvjt4z75g4rshngdxn5fr6qsccl78fgsz52fefwemxzvmaqptdfnp"
+                    .to_string(),
+                provided: "other_nft".to_string(),
+            }
+        );
+
+        // 5. A signature made for another chain or another minter contract is refused
+        let mint_request = MintRequest {
+            nonce: Uint64::new(4),
+            ..mint_request
+        };
+        let mut other_env = env.clone();
+        other_env.block.chain_id = "columbus-5".to_string();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: sign_helper(&other_env, &mint_request),
+                    adr036_signer: None,
+                }],
+                mint_request: mint_request.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+        let mut other_env = env.clone();
+        other_env.contract.address = Addr::unchecked("other_minter");
+        let err = execute(
             deps.as_mut(),
             env,
             info,
             ExecuteMsg::Mint {
-                mint_request: MintRequest {
-                    mint_msg,
-                    nft_contract,
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: sign_helper(&other_env, &mint_request),
+                    adr036_signer: None,
+                }],
+                mint_request,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+    }
+
+    #[test]
+    fn test_minter_keys() {
+        let mut deps = mock_dependencies();
+        init_helper(deps.as_mut());
+
+        let info = mock_info("creator", &coins(456, "uluna"));
+        let env = mock_env();
+        let nft_contract =
+            "terra1sf2fftvjt4z75g4rshngdxn5fr6qsccl78fgsz52fefwemxzvmaqptdfnp".to_string();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetNftContract {
+                nft_contract: nft_contract.clone(),
+            },
+        )
+        .unwrap();
+
+        // 1. The instantiation key is the default key of the keyring
+        let keys: MinterKeysResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::MinterKeys {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            keys.keys,
+            vec![MinterKey {
+                key_id: "default".to_string(),
+                pubkey: authority_pubkey(),
+                scheme: KeyScheme::Secp256k1,
+                valid_from: None,
+                valid_until: None,
+            }]
+        );
+
+        // 2. The owner adds a key that will be valid in 100 seconds
+        let rotation_key = SigningKey::from_bytes(&[9u8; 32]).unwrap();
+        let add_msg = ExecuteMsg::AddMinterKey {
+            key_id: "rotation".to_string(),
+            pubkey: base64::encode(rotation_key.verifying_key().to_bytes()),
+            scheme: None,
+            valid_from: Some(env.block.time.plus_seconds(100)),
+            valid_until: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bad_person", &[]),
+            add_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            add_msg.clone(),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            add_msg,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::MinterKeyAlreadyExists {
+                key_id: "rotation".to_string()
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::AddMinterKey {
+                key_id: "empty_window".to_string(),
+                pubkey: authority_pubkey(),
+                scheme: None,
+                valid_from: Some(env.block.time),
+                valid_until: Some(env.block.time),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidKeyWindow {}
+        );
+
+        // 3. The new key can't be used before it is valid
+        let mint_request = MintRequest {
+            mint_msg: MintMsg {
+                token_id: "1".to_string(),
+                owner: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            nft_contract,
+            nonce: Uint64::new(1),
+            expires_at: Expiration::AtHeight(1_000_000),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![MintSignature {
+                    key_id: Some("rotation".to_string()),
+                    signature: sign_with(&rotation_key, &env, &mint_request),
+                    adr036_signer: None,
+                }],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::MinterKeyInactive {
+                key_id: "rotation".to_string()
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: sign_with(&rotation_key, &env, &mint_request),
+                    adr036_signer: None,
+                }],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+
+        // 4. Once valid, the signing key is found without being specified
+        let mut later_env = env.clone();
+        later_env.block.time = env.block.time.plus_seconds(100);
+        let res = execute(
+            deps.as_mut(),
+            later_env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: sign_with(&rotation_key, &later_env, &mint_request),
+                    adr036_signer: None,
+                }],
+            },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[1], attr("key_id", "rotation"));
+
+        // 5. A revoked key can't sign anymore
+        execute(
+            deps.as_mut(),
+            later_env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::RevokeMinterKey {
+                key_id: "default".to_string(),
+            },
+        )
+        .unwrap();
+        let mint_request = MintRequest {
+            nonce: Uint64::new(2),
+            ..mint_request
+        };
+        let err = execute(
+            deps.as_mut(),
+            later_env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![MintSignature {
+                    key_id: Some("default".to_string()),
+                    signature: sign_helper(&later_env, &mint_request),
+                    adr036_signer: None,
+                }],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::MinterKeyNotFound {
+                key_id: "default".to_string()
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            later_env.clone(),
+            info,
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: sign_helper(&later_env, &mint_request),
+                    adr036_signer: None,
+                }],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+
+        let keys: MinterKeysResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                later_env,
+                QueryMsg::MinterKeys {
+                    start_after: None,
+                    limit: None,
                 },
-                signature: signature.to_string(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(keys.keys.len(), 1);
+        assert_eq!(keys.keys[0].key_id, "rotation".to_string());
+    }
+
+    #[test]
+    fn test_threshold() {
+        let mut deps = mock_dependencies();
+        init_helper(deps.as_mut());
+
+        let info = mock_info("creator", &coins(456, "uluna"));
+        let env = mock_env();
+        let nft_contract =
+            "terra1sf2fftvjt4z75g4rshngdxn5fr6qsccl78fgsz52fefwemxzvmaqptdfnp".to_string();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetNftContract {
+                nft_contract: nft_contract.clone(),
+            },
+        )
+        .unwrap();
+
+        // 1. The project team gets its own key, and both keys have to sign
+        let project_key = SigningKey::from_bytes(&[9u8; 32]).unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::AddMinterKey {
+                key_id: "project".to_string(),
+                pubkey: base64::encode(project_key.verifying_key().to_bytes()),
+                scheme: None,
+                valid_from: None,
+                valid_until: None,
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetThreshold { threshold: 3 },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidThreshold { threshold: 3 }
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bad_person", &[]),
+            ExecuteMsg::SetThreshold { threshold: 2 },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetThreshold { threshold: 2 },
+        )
+        .unwrap();
+
+        // 2. One signature is not enough, even repeated
+        let mint_request = MintRequest {
+            mint_msg: MintMsg {
+                token_id: "1".to_string(),
+                owner: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            nft_contract,
+            nonce: Uint64::new(1),
+            expires_at: Expiration::AtHeight(1_000_000),
+        };
+        let authority_signature = MintSignature {
+            key_id: Some("default".to_string()),
+            signature: sign_helper(&env, &mint_request),
+            adr036_signer: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![
+                    authority_signature.clone(),
+                    MintSignature {
+                        key_id: None,
+                        signature: sign_helper(&env, &mint_request),
+                        adr036_signer: None,
+                    },
+                ],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::NotEnoughSignatures {
+                threshold: 2,
+                provided: 1
+            }
+        );
+
+        // 3. An invalid signature is refused
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![
+                    authority_signature.clone(),
+                    MintSignature {
+                        key_id: Some("project".to_string()),
+                        signature: sign_helper(&env, &mint_request),
+                        adr036_signer: None,
+                    },
+                ],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+
+        // 4. Both keys sign
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![
+                    authority_signature,
+                    MintSignature {
+                        key_id: None,
+                        signature: sign_with(&project_key, &env, &mint_request),
+                        adr036_signer: None,
+                    },
+                ],
             },
         )
         .unwrap();
+        assert_eq!(res.attributes[1], attr("key_id", "default"));
+        assert_eq!(res.attributes[2], attr("key_id", "project"));
+
+        // 5. Expired keys don't count towards the threshold
+        let expired_key = SigningKey::from_bytes(&[10u8; 32]).unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::AddMinterKey {
+                key_id: "expired".to_string(),
+                pubkey: base64::encode(expired_key.verifying_key().to_bytes()),
+                scheme: None,
+                valid_from: None,
+                valid_until: Some(env.block.time),
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetThreshold { threshold: 3 },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidThreshold { threshold: 3 }
+        );
+
+        // 6. A key can't be revoked if the threshold can't be reached without it
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::RevokeMinterKey {
+                key_id: "project".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::ThresholdNotReachable {
+                key_id: "project".to_string(),
+                threshold: 2
+            }
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::RevokeMinterKey {
+                key_id: "expired".to_string(),
+            },
+        )
+        .unwrap();
+
+        // 7. A public key can only be registered once, its signatures would only be counted once
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::AddMinterKey {
+                key_id: "project_copy".to_string(),
+                pubkey: base64::encode(project_key.verifying_key().to_bytes()),
+                scheme: None,
+                valid_from: None,
+                valid_until: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::MinterPubkeyAlreadyRegistered {
+                key_id: "project".to_string()
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetMinter {
+                minter: base64::encode(project_key.verifying_key().to_bytes()),
+                scheme: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::MinterPubkeyAlreadyRegistered {
+                key_id: "project".to_string()
+            }
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetMinter {
+                minter: authority_pubkey(),
+                scheme: None,
+            },
+        )
+        .unwrap();
+
+        let keys: MinterKeysResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::MinterKeys {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(keys.threshold, 2);
+        assert_eq!(keys.keys.len(), 2);
+    }
+
+    #[test]
+    fn test_ed25519_key() {
+        let mut deps = mock_dependencies();
+        init_helper(deps.as_mut());
+
+        let info = mock_info("creator", &coins(456, "uluna"));
+        let env = mock_env();
+        let nft_contract =
+            "terra1sf2fftvjt4z75g4rshngdxn5fr6qsccl78fgsz52fefwemxzvmaqptdfnp".to_string();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetNftContract {
+                nft_contract: nft_contract.clone(),
+            },
+        )
+        .unwrap();
+
+        // 1. Malformed keys can't be stored
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetMinter {
+                minter: authority_pubkey(),
+                scheme: Some(KeyScheme::Ed25519),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidPubkey {}
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::AddMinterKey {
+                key_id: "hsm".to_string(),
+                pubkey: "any_pu_bkey".to_string(),
+                scheme: None,
+                valid_from: None,
+                valid_until: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidPubkey {}
+        );
+
+        // 2. An ed25519 key signs the request hash
+        let hsm_key = ed25519_zebra::SigningKey::from([11u8; 32]);
+        let hsm_pubkey: [u8; 32] = ed25519_zebra::VerificationKey::from(&hsm_key).into();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::AddMinterKey {
+                key_id: "hsm".to_string(),
+                pubkey: base64::encode(hsm_pubkey),
+                scheme: Some(KeyScheme::Ed25519),
+                valid_from: None,
+                valid_until: None,
+            },
+        )
+        .unwrap();
+
+        let mint_request = MintRequest {
+            mint_msg: MintMsg {
+                token_id: "1".to_string(),
+                owner: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            nft_contract,
+            nonce: Uint64::new(1),
+            expires_at: Expiration::AtHeight(1_000_000),
+        };
+        let signature: [u8; 64] = hsm_key
+            .sign(&request_hash(&env, &mint_request).unwrap())
+            .into();
+        let res = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::Mint {
+                mint_request,
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: base64::encode(signature),
+                    adr036_signer: None,
+                }],
+            },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[1], attr("key_id", "hsm"));
+    }
+
+    #[test]
+    fn test_adr036_signature() {
+        let mut deps = mock_dependencies();
+        init_helper(deps.as_mut());
+
+        let info = mock_info("creator", &coins(456, "uluna"));
+        let env = mock_env();
+        let nft_contract =
+            "terra1sf2fftvjt4z75g4rshngdxn5fr6qsccl78fgsz52fefwemxzvmaqptdfnp".to_string();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetNftContract {
+                nft_contract: nft_contract.clone(),
+            },
+        )
+        .unwrap();
+
+        let mint_request = MintRequest {
+            mint_msg: MintMsg {
+                token_id: "1".to_string(),
+                owner: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            nft_contract,
+            nonce: Uint64::new(1),
+            expires_at: Expiration::AtHeight(1_000_000),
+        };
+
+        // The wallet of the authority signs the signing bytes with signArbitrary
+        let wallet = "terra1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd8";
+        let data = signing_bytes(
+            &env.block.chain_id,
+            env.contract.address.as_str(),
+            &mint_request,
+        )
+        .unwrap();
+        let sign_doc = format!(
+            r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"sequence":"0"}}"#,
+            base64::encode(&data),
+            wallet
+        );
+        let signature: Signature = authority_key().sign(sign_doc.as_bytes());
+        let wallet_signature = base64::encode(signature.as_ref());
+
+        // 1. The signature is not a signature of the raw request
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![MintSignature {
+                    key_id: Some("default".to_string()),
+                    signature: wallet_signature.clone(),
+                    adr036_signer: None,
+                }],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+
+        // 2. Nor of a sign doc from another wallet
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: vec![MintSignature {
+                    key_id: Some("default".to_string()),
+                    signature: wallet_signature.clone(),
+                    adr036_signer: Some("terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string()),
+                }],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+
+        // 3. The sign doc is rebuilt from the signer address
+        execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::Mint {
+                mint_request,
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: wallet_signature,
+                    adr036_signer: Some(wallet.to_string()),
+                }],
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_mint_with_proof() {
+        let mut deps = mock_dependencies();
+        init_helper(deps.as_mut());
+
+        let info = mock_info("creator", &coins(456, "uluna"));
+        let env = mock_env();
+        let nft_contract =
+            "terra1sf2fftvjt4z75g4rshngdxn5fr6qsccl78fgsz52fefwemxzvmaqptdfnp".to_string();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::SetNftContract { nft_contract },
+        )
+        .unwrap();
+
+        // Snapshot of 2 NFTs
+        let mint_msg = MintMsg {
+            token_id: "1".to_string(),
+            owner: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+            token_uri: Some("ipfs://1".to_string()),
+            extension: None,
+        };
+        let other_mint_msg = MintMsg {
+            token_id: "2".to_string(),
+            ..mint_msg.clone()
+        };
+        let leaf = merkle_leaf(&mint_msg).unwrap();
+        let other_leaf = merkle_leaf(&other_mint_msg).unwrap();
+        let (first, second) = if leaf < other_leaf {
+            (&leaf, &other_leaf)
+        } else {
+            (&other_leaf, &leaf)
+        };
+        let merkle_root =
+            Binary::from(Sha256::new().chain(first).chain(second).finalize().to_vec());
+        let proof = vec![Binary::from(other_leaf)];
+
+        // 1. The owner has to publish the root first
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::MintWithProof {
+                mint_msg: mint_msg.clone(),
+                proof: proof.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::MerkleRootNotSet {}
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bad_person", &[]),
+            ExecuteMsg::SetMerkleRoot {
+                merkle_root: merkle_root.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetMerkleRoot {
+                merkle_root: Binary::from(b"root".to_vec()),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidMerkleRoot {}
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetMerkleRoot {
+                merkle_root: merkle_root.clone(),
+            },
+        )
+        .unwrap();
+        let res: MerkleRootResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::MerkleRoot {}).unwrap())
+                .unwrap();
+        assert_eq!(res.merkle_root, Some(merkle_root));
+
+        // 2. A mint message that is not in the snapshot is refused
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::MintWithProof {
+                mint_msg: MintMsg {
+                    owner: "terra1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd8".to_string(),
+                    ..mint_msg.clone()
+                },
+                proof: proof.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidProof {}
+        );
+
+        // 3. The fee has to be paid
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::MintWithProof {
+                mint_msg: mint_msg.clone(),
+                proof: proof.clone(),
+            },
+        )
+        .unwrap_err();
+
+        // 4. A leaf can only be minted once
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::MintWithProof {
+                mint_msg: mint_msg.clone(),
+                proof: proof.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 2);
+        let err = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::MintWithProof { mint_msg, proof },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::LeafAlreadyClaimed {}
+        );
+    }
+
+    #[test]
+    fn test_verify_mint_request() {
+        let mut deps = mock_dependencies();
+        init_helper(deps.as_mut());
+
+        let env = mock_env();
+        let nft_contract =
+            "terra1sf2fftvjt4z75g4rshngdxn5fr6qsccl78fgsz52fefwemxzvmaqptdfnp".to_string();
+        let mint_request = MintRequest {
+            mint_msg: MintMsg {
+                token_id: "1".to_string(),
+                owner: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            nft_contract: nft_contract.clone(),
+            nonce: Uint64::new(1),
+            expires_at: Expiration::AtHeight(env.block.height + 100),
+        };
+        let signatures = vec![MintSignature {
+            key_id: None,
+            signature: sign_helper(&env, &mint_request),
+            adr036_signer: None,
+        }];
+        let verify = |deps: Deps, env: Env, mint_request: &MintRequest<Extension>, funds| {
+            from_binary::<VerifyMintRequestResponse>(
+                &query(
+                    deps,
+                    env,
+                    QueryMsg::VerifyMintRequest {
+                        mint_request: mint_request.clone(),
+                        signatures: signatures.clone(),
+                        funds,
+                        cw20_funds: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        // 1. The nft contract is not set yet
+        let res = verify(
+            deps.as_ref(),
+            env.clone(),
+            &mint_request,
+            coins(456, "uluna"),
+        );
+        assert!(!res.valid);
+        assert_eq!(res.failed_check, Some(MintCheck::ContractInitialized));
+        assert_eq!(
+            res.error,
+            Some(ContractError::ContractNotInitialized {}.to_string())
+        );
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetNftContract { nft_contract },
+        )
+        .unwrap();
+
+        // 2. The fee is not paid
+        let res = verify(
+            deps.as_ref(),
+            env.clone(),
+            &mint_request,
+            coins(45, "uluna"),
+        );
+        assert_eq!(res.failed_check, Some(MintCheck::Fee));
+
+        // 3. The request expired
+        let mut later_env = env.clone();
+        later_env.block.height += 100;
+        let res = verify(deps.as_ref(), later_env, &mint_request, coins(456, "uluna"));
+        assert_eq!(res.failed_check, Some(MintCheck::Expiration));
+
+        // 4. The signature was made for another request
+        let other_request = MintRequest {
+            nonce: Uint64::new(2),
+            ..mint_request.clone()
+        };
+        let res = verify(
+            deps.as_ref(),
+            env.clone(),
+            &other_request,
+            coins(456, "uluna"),
+        );
+        assert_eq!(res.failed_check, Some(MintCheck::Signature));
+        assert_eq!(res.error, Some(ContractError::Unauthorized {}.to_string()));
+
+        // 5. The request is valid, until it is used
+        let res = verify(
+            deps.as_ref(),
+            env.clone(),
+            &mint_request,
+            coins(456, "uluna"),
+        );
+        assert_eq!(
+            res,
+            VerifyMintRequestResponse {
+                valid: true,
+                failed_check: None,
+                error: None,
+                key_ids: vec!["default".to_string()],
+            }
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &coins(456, "uluna")),
+            ExecuteMsg::Mint {
+                mint_request: mint_request.clone(),
+                signatures: signatures.clone(),
+            },
+        )
+        .unwrap();
+        let res = verify(deps.as_ref(), env, &mint_request, coins(456, "uluna"));
+        assert_eq!(res.failed_check, Some(MintCheck::AlreadyUsed));
+    }
+
+    #[test]
+    fn test_fee_split() {
+        let mut deps = mock_dependencies();
+        init_helper(deps.as_mut());
+
+        let env = mock_env();
+        let nft_contract =
+            "terra1sf2fftvjt4z75g4rshngdxn5fr6qsccl78fgsz52fefwemxzvmaqptdfnp".to_string();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetNftContract {
+                nft_contract: nft_contract.clone(),
+            },
+        )
+        .unwrap();
+
+        // 1. The owner shares the price between three parties, the shares have to sum to 10000 bps
+        let shares = vec![
+            FeeShare {
+                recipient: "artist".to_string(),
+                bps: 3333,
+            },
+            FeeShare {
+                recipient: "dao".to_string(),
+                bps: 3333,
+            },
+            FeeShare {
+                recipient: "meonly".to_string(),
+                bps: 3333,
+            },
+        ];
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetFeeSplit {
+                prices: coins(100, "ibc/usdc"),
+                shares: shares.clone(),
+                dust_recipient: "artist".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidFeeShares { total_bps: 9999 }
+        );
+
+        let mut shares = shares;
+        shares[2].bps = 3334;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetFeeSplit {
+                prices: coins(100, "ibc/usdc"),
+                shares: shares.clone(),
+                dust_recipient: "artist".to_string(),
+            },
+        )
+        .unwrap();
+        let res: FeeResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::FeePrice {}).unwrap())
+                .unwrap();
+        assert_eq!(
+            res,
+            FeeResponse {
+                current_tier: PriceTier {
+                    starting_at: Timestamp::from_seconds(0),
+                    prices: coins(100, "ibc/usdc"),
+                    cw20_prices: vec![],
+                },
+                next_tier: None,
+                fee_split: FeeSplit {
+                    shares,
+                    dust_recipient: "artist".to_string(),
+                },
+            }
+        );
+
+        let mint_request = |nonce: u64| MintRequest {
+            mint_msg: MintMsg {
+                token_id: nonce.to_string(),
+                owner: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            nft_contract: nft_contract.clone(),
+            nonce: Uint64::new(nonce),
+            expires_at: Expiration::AtHeight(1_000_000),
+        };
+        let mint = |deps: DepsMut, mint_request: MintRequest<Extension>, funds: &[Coin]| {
+            execute(
+                deps,
+                env.clone(),
+                mock_info("creator", funds),
+                ExecuteMsg::Mint {
+                    signatures: vec![MintSignature {
+                        key_id: None,
+                        signature: sign_helper(&env, &mint_request),
+                        adr036_signer: None,
+                    }],
+                    mint_request,
+                },
+            )
+        };
+
+        // 2. The fee is split in the denom it was paid in, the rounding dust goes to the artist
+        let res = mint(deps.as_mut(), mint_request(1), &coins(100, "ibc/usdc")).unwrap();
+        assert_eq!(
+            res.messages[1..]
+                .iter()
+                .map(|msg| msg.msg.clone())
+                .collect::<Vec<CosmosMsg>>(),
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "artist".to_string(),
+                    amount: coins(34, "ibc/usdc"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "dao".to_string(),
+                    amount: coins(33, "ibc/usdc"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "meonly".to_string(),
+                    amount: coins(33, "ibc/usdc"),
+                })
+            ]
+        );
+
+        // 3. The funds have to cover the price
+        let err = mint(deps.as_mut(), mint_request(2), &coins(99, "ibc/usdc")).unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::FeeNotPaidCorrectly {
+                required: "100ibc/usdc".to_string(),
+                provided: "99ibc/usdc".to_string(),
+            }
+        );
+
+        // 4. Denoms without a price are refused
+        let err = mint(deps.as_mut(), mint_request(2), &coins(456, "uluna")).unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::FeeDenomNotAccepted {
+                denom: "uluna".to_string(),
+            }
+        );
+
+        // 5. The surplus and the other coins are refunded to the sender
+        let res = mint(
+            deps.as_mut(),
+            mint_request(2),
+            &[coin(456, "ibc/usdc"), coin(7, "uluna")],
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[4].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: vec![coin(356, "ibc/usdc"), coin(7, "uluna")],
+            })
+        );
+        assert_eq!(
+            res.attributes[2..],
+            [attr("refund", "356ibc/usdc"), attr("refund", "7uluna")]
+        );
+    }
+
+    #[test]
+    fn test_cw20_fee() {
+        let mut deps = mock_dependencies();
+        init_helper(deps.as_mut());
+
+        let env = mock_env();
+        let nft_contract =
+            "terra1sf2fftvjt4z75g4rshngdxn5fr6qsccl78fgsz52fefwemxzvmaqptdfnp".to_string();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetNftContract {
+                nft_contract: nft_contract.clone(),
+            },
+        )
+        .unwrap();
+
+        // 1. Only the owner can accept a cw20 token
+        let set_cw20_prices = ExecuteMsg::SetCw20Prices {
+            prices: vec![Cw20Coin {
+                address: "token".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bad_person", &[]),
+            set_cw20_prices.clone(),
+        )
+        .unwrap_err();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            set_cw20_prices,
+        )
+        .unwrap();
+
+        let mint_request = |nonce: u64| MintRequest {
+            mint_msg: MintMsg {
+                token_id: nonce.to_string(),
+                owner: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            nft_contract: nft_contract.clone(),
+            nonce: Uint64::new(nonce),
+            expires_at: Expiration::AtHeight(1_000_000),
+        };
+        let receive = |deps: DepsMut, token: &str, amount: u128, nonce: u64| {
+            let mint_request = mint_request(nonce);
+            execute(
+                deps,
+                env.clone(),
+                mock_info(token, &[]),
+                ExecuteMsg::Receive(Cw20ReceiveMsg {
+                    sender: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+                    amount: Uint128::from(amount),
+                    msg: to_binary(&ReceiveMsg::Mint {
+                        signatures: vec![MintSignature {
+                            key_id: None,
+                            signature: sign_helper(&env, &mint_request),
+                            adr036_signer: None,
+                        }],
+                        mint_request,
+                    })
+                    .unwrap(),
+                }),
+            )
+        };
+
+        // 2. The paid tokens are forwarded to the fee recipients
+        let res = receive(deps.as_mut(), "token", 1000, 1).unwrap();
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "meonly".to_string(),
+                    amount: Uint128::from(1000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // 3. The surplus is refunded to the sender of the tokens
+        let res = receive(deps.as_mut(), "token", 1500, 2).unwrap();
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+                    amount: Uint128::from(500u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(res.attributes[2], attr("refund", "500token"));
+
+        // 4. The price has to be covered, with an accepted token
+        let err = receive(deps.as_mut(), "token", 999, 3).unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::FeeNotPaidCorrectly {
+                required: "1000token".to_string(),
+                provided: "999token".to_string(),
+            }
+        );
+        let err = receive(deps.as_mut(), "other_token", 1000, 3).unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::FeeDenomNotAccepted {
+                denom: "other_token".to_string(),
+            }
+        );
+
+        // 5. Native coins can't be sent along with the cw20 tokens
+        let request = mint_request(3);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &coins(456, "uluna")),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+                amount: Uint128::from(1000u128),
+                msg: to_binary(&ReceiveMsg::Mint {
+                    signatures: vec![MintSignature {
+                        key_id: None,
+                        signature: sign_helper(&env, &request),
+                        adr036_signer: None,
+                    }],
+                    mint_request: request,
+                })
+                .unwrap(),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::FeeDenomNotAccepted {
+                denom: "uluna".to_string(),
+            }
+        );
+        // 6. A cw20 payment can be checked before it is sent
+        let verify = |funds: Vec<Coin>, token: &str, amount: u128| {
+            let mint_request = mint_request(3);
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::VerifyMintRequest {
+                    signatures: vec![MintSignature {
+                        key_id: None,
+                        signature: sign_helper(&env, &mint_request),
+                        adr036_signer: None,
+                    }],
+                    mint_request,
+                    funds,
+                    cw20_funds: Some(Cw20Coin {
+                        address: token.to_string(),
+                        amount: Uint128::from(amount),
+                    }),
+                },
+            )
+            .map(|res| from_binary::<VerifyMintRequestResponse>(&res).unwrap())
+        };
+        assert!(verify(vec![], "token", 1000).unwrap().valid);
+        let res = verify(vec![], "token", 999).unwrap();
+        assert_eq!(res.failed_check, Some(MintCheck::Fee));
+        assert_eq!(
+            res.error,
+            Some(
+                ContractError::FeeNotPaidCorrectly {
+                    required: "1000token".to_string(),
+                    provided: "999token".to_string(),
+                }
+                .to_string()
+            )
+        );
+        verify(coins(456, "uluna"), "token", 1000).unwrap_err();
+    }
+
+    #[test]
+    fn test_price_schedule() {
+        let mut deps = mock_dependencies();
+        init_helper(deps.as_mut());
+
+        let env = mock_env();
+        let nft_contract =
+            "terra1sf2fftvjt4z75g4rshngdxn5fr6qsccl78fgsz52fefwemxzvmaqptdfnp".to_string();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetNftContract {
+                nft_contract: nft_contract.clone(),
+            },
+        )
+        .unwrap();
+
+        // 1. The mint is free for the first 30 days, then costs 2 LUNA. The tiers have to be sorted
+        let free_tier = PriceTier {
+            starting_at: env.block.time,
+            prices: vec![],
+            cw20_prices: vec![],
+        };
+        let paid_tier = PriceTier {
+            starting_at: env.block.time.plus_seconds(30 * 24 * 3600),
+            prices: coins(2_000_000, "uluna"),
+            cw20_prices: vec![],
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetPriceSchedule {
+                tiers: vec![paid_tier.clone(), free_tier.clone()],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidPriceSchedule {}
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bad_person", &[]),
+            ExecuteMsg::SetPriceSchedule {
+                tiers: vec![free_tier.clone(), paid_tier.clone()],
+            },
+        )
+        .unwrap_err();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetPriceSchedule {
+                tiers: vec![free_tier.clone(), paid_tier.clone()],
+            },
+        )
+        .unwrap();
+
+        let fee_price = |deps: Deps, env: Env| {
+            from_binary::<FeeResponse>(&query(deps, env, QueryMsg::FeePrice {}).unwrap()).unwrap()
+        };
+        let mint = |deps: DepsMut, env: Env, nonce: u64, funds: &[Coin]| {
+            let mint_request = MintRequest {
+                mint_msg: MintMsg {
+                    token_id: nonce.to_string(),
+                    owner: "terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string(),
+                    token_uri: None,
+                    extension: None,
+                },
+                nft_contract: nft_contract.clone(),
+                nonce: Uint64::new(nonce),
+                expires_at: Expiration::AtHeight(1_000_000),
+            };
+            execute(
+                deps,
+                env.clone(),
+                mock_info("creator", funds),
+                ExecuteMsg::Mint {
+                    signatures: vec![MintSignature {
+                        key_id: None,
+                        signature: sign_helper(&env, &mint_request),
+                        adr036_signer: None,
+                    }],
+                    mint_request,
+                },
+            )
+        };
+
+        // 2. During the first tier, the mint is free
+        let res = fee_price(deps.as_ref(), env.clone());
+        assert_eq!(res.current_tier, free_tier);
+        assert_eq!(res.next_tier, Some(paid_tier.clone()));
+        let res = mint(deps.as_mut(), env.clone(), 1, &[]).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // 3. Once the second tier started, its price applies
+        let mut later_env = env.clone();
+        later_env.block.time = paid_tier.starting_at;
+        let res = fee_price(deps.as_ref(), later_env.clone());
+        assert_eq!(res.current_tier, paid_tier);
+        assert_eq!(res.next_tier, None);
+        let err = mint(deps.as_mut(), later_env.clone(), 2, &[]).unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::FeeNotPaid {}
+        );
+        mint(deps.as_mut(), later_env, 2, &coins(2_000_000, "uluna")).unwrap();
+
+        // 4. Before the first tier, the base prices apply
+        let mut earlier_env = env;
+        earlier_env.block.time = earlier_env.block.time.minus_seconds(1);
+        let res = fee_price(deps.as_ref(), earlier_env);
+        assert_eq!(res.current_tier.prices, coins(456, "uluna"));
+        assert_eq!(res.next_tier, Some(free_tier));
     }
 }
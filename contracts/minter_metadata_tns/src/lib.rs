@@ -2,12 +2,21 @@
 use anyhow::Result;
 use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Response};
 
+use cw2::set_contract_version;
+
 use minter_export::contract::{
-    execute as minter_execute, instantiate as minter_instantiate, query as minter_query,
+    execute as minter_execute, instantiate as minter_instantiate, migrate as minter_migrate,
+    query as minter_query,
 };
-use minter_export::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use minter_export::error::ContractError;
+use minter_export::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 // This is a simple type to let us handle empty extensions
 pub type Extension = Option<Metadata>;
 
@@ -41,6 +50,7 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     minter_instantiate(deps, env, info, msg)
 }
 
@@ -55,26 +65,36 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg<Extension>) -> Result<Binary> {
     minter_query(deps, env, msg)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    minter_migrate(deps, env, msg, CONTRACT_NAME, CONTRACT_VERSION)
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, Uint128};
+    use cosmwasm_std::{coins, Uint64};
     use cw721_base::MintMsg;
-    use minter_export::msg::MintRequest;
+    use minter_export::msg::{Expiration, MintRequest, MintSignature};
+    use minter_export::state::FeeShare;
     fn init_helper(deps: DepsMut) -> Response {
         let instantiate_msg = InstantiateMsg {
             name: "fee_contract".to_string(),
             owner: None,
-            treasury: Some("meonly".to_string()),
-            fee_price: Uint128::from(456u128),
-            project_price: Uint128::from(0u128),
-            project_treasury: "meonly".to_string(),
+            prices: coins(456, "uluna"),
+            fee_shares: vec![FeeShare {
+                recipient: "meonly".to_string(),
+                bps: 10000,
+            }],
+            dust_recipient: None,
             minter: "AjBui2DTkLVKo2p5uCXf68SbHzSDoRDESKNtsr3+vtJI".to_string(),
+            minter_scheme: None,
+            threshold: None,
         };
         let info = mock_info("creator", &[]);
         let env = mock_env();
@@ -118,32 +138,30 @@ pub mod tests {
     }
 
     #[test]
-    fn test_update_fee_price() {
+    fn test_update_fee_split() {
         let mut deps = mock_dependencies();
         init_helper(deps.as_mut());
 
         let info = mock_info("creator", &[]);
         let env = mock_env();
-        execute(
-            deps.as_mut(),
-            env.clone(),
-            info,
-            ExecuteMsg::SetFeePrice {
-                price: Uint128::from(899898u128),
-            },
-        )
-        .unwrap();
+        let fee_split = ExecuteMsg::SetFeeSplit {
+            prices: coins(899898, "uluna"),
+            shares: vec![
+                FeeShare {
+                    recipient: "artist".to_string(),
+                    bps: 5000,
+                },
+                FeeShare {
+                    recipient: "meonly".to_string(),
+                    bps: 5000,
+                },
+            ],
+            dust_recipient: "meonly".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info, fee_split.clone()).unwrap();
 
         let info = mock_info("bad_person", &[]);
-        execute(
-            deps.as_mut(),
-            env,
-            info,
-            ExecuteMsg::SetFeePrice {
-                price: Uint128::from(909009898u128),
-            },
-        )
-        .unwrap_err();
+        execute(deps.as_mut(), env, info, fee_split).unwrap_err();
     }
 
     #[test]
@@ -158,7 +176,8 @@ pub mod tests {
             env.clone(),
             info,
             ExecuteMsg::SetMinter {
-                minter: "any_pu_bkey".to_string(),
+                minter: "A8Bd8ZrrMkJNrJlnb29x5vVeYnsPYAlEsTNYwvf0gtHg".to_string(),
+                scheme: None,
             },
         )
         .unwrap();
@@ -169,7 +188,8 @@ pub mod tests {
             env,
             info,
             ExecuteMsg::SetMinter {
-                minter: "any_pu_bkey".to_string(),
+                minter: "A8Bd8ZrrMkJNrJlnb29x5vVeYnsPYAlEsTNYwvf0gtHg".to_string(),
+                scheme: None,
             },
         )
         .unwrap_err();
@@ -211,8 +231,14 @@ pub mod tests {
                 mint_request: MintRequest {
                     mint_msg: mint_msg.clone(),
                     nft_contract: nft_contract.clone(),
+                    nonce: Uint64::new(1),
+                    expires_at: Expiration::AtHeight(1_000_000),
                 },
-                signature: signature.to_string(),
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: signature.to_string(),
+                    adr036_signer: None,
+                }],
             },
         )
         .unwrap_err();
@@ -226,8 +252,14 @@ pub mod tests {
                 mint_request: MintRequest {
                     mint_msg,
                     nft_contract,
+                    nonce: Uint64::new(1),
+                    expires_at: Expiration::AtHeight(1_000_000),
                 },
-                signature: signature.to_string(),
+                signatures: vec![MintSignature {
+                    key_id: None,
+                    signature: signature.to_string(),
+                    adr036_signer: None,
+                }],
             },
         )
         .unwrap_err();
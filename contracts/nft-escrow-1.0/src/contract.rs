@@ -1,24 +1,45 @@
 #[cfg(not(feature = "library"))]
 use anyhow::{anyhow, Result};
 use cosmwasm_std::{
-    entry_point, from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
-    StdError, StdResult, Timestamp,
+    entry_point, from_binary, to_binary, to_vec, Addr, Binary, ContractResult, Deps, DepsMut,
+    Empty, Env, MessageInfo, Order, Pair, QueryRequest, Response, StdError, StdResult,
+    SystemResult, Timestamp, WasmQuery,
 };
 use cw_storage_plus::Bound;
 
 use escrow_export_classic::msg::{
-    to_token_info, ContractInfoResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
-    ReceiveMsg, TokenInfo, TokenInfoResponse,
+    into_cosmos_msg, to_snapshot_response, to_token_info, CollectionsResponse,
+    ContractInfoResponse, DepositSnapshotResponse, EventsResponse, ExecuteMsg, InstantiateMsg,
+    MigrateMsg, QueryMsg, ReceiveMsg, TokenInfo, TokenInfoResponse, TokenKey,
+};
+use escrow_export_classic::state::{
+    ContractInfo, DepositSnapshot, Event, EventKind, LockPolicy, TokenOwner,
 };
-use escrow_export_classic::state::{ContractInfo, TokenOwner};
 
 use crate::error::ContractError;
-use crate::state::{is_owner, DepositNft, CONTRACT_INFO};
+use crate::state::{
+    is_depositor_or_operator, is_legacy_token_key, is_owner, is_owner_or_relayer,
+    legacy_token_range_start, log_event, migrated_key, parse_token_key, token_key,
+    token_range_start, DepositNft, LegacyDepositNft, COLLECTIONS, CONTRACT_INFO, EVENTS,
+    LEGACY_COLLECTION, LEGACY_CONTRACT_INFO, OPERATORS, SNAPSHOTS,
+};
+
+use cw2::{get_contract_version, set_contract_version};
+use cw721::{Cw721ExecuteMsg, Cw721QueryMsg, NftInfoResponse, OwnerOfResponse};
+use migration_utils::json::JsonValue;
+use migration_utils::version::parse_version;
+use sha2::{Digest, Sha256};
+
+const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const DEFAULT_DESTINATION_PREFIX: &str = "terra";
+const DEFAULT_MIGRATION_LIMIT: u32 = 200;
 
 /**
  * This package is used to lock NFTs
  * It also allows to query deposited tokens and the address that deposited them easily
- * This escrow contract only accepts one NFT (e.g. Galactic Punks)
+ * This escrow contract accepts NFTs from every collection registered by the owner (e.g. Galactic Punks)
+ * The lock policy chosen at instantiation decides if (and until when) the NFTs can be withdrawn
  * */
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -32,13 +53,25 @@ pub fn instantiate(
     // store token info
     let data = ContractInfo {
         name: msg.name,
-        nft_address: deps.api.addr_validate(&msg.nft_address)?,
         owner: msg
             .owner
             .map(|x| deps.api.addr_validate(&x))
             .unwrap_or(Ok(info.sender))?,
+        relayer: msg
+            .relayer
+            .map(|x| deps.api.addr_validate(&x))
+            .transpose()?,
+        withdraw_window: msg.withdraw_window,
+        lock_policy: msg.lock_policy,
+        destination_prefix: msg.destination_prefix,
     };
     CONTRACT_INFO.save(deps.storage, &data)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    // store the accepted collections
+    for collection in msg.collections {
+        let collection_addr = deps.api.addr_validate(&collection)?;
+        COLLECTIONS.save(deps.storage, &collection_addr, &Empty {})?;
+    }
     Ok(Response::default()
         .add_attribute("action", "init")
         .add_attribute("contract_name", "one_sided_escrow_contract"))
@@ -53,33 +86,223 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> R
             msg,
         } => execute_receive_nft(deps, env, info, sender, token_id, msg),
 
+        ExecuteMsg::Withdraw {
+            collection,
+            token_id,
+        } => execute_withdraw(deps, env, info, collection, token_id),
+
         ExecuteMsg::SetOwner { owner } => set_owner(deps, env, info, owner),
 
-        ExecuteMsg::Withdraw { .. } => Err(anyhow!(ContractError::Unauthorized {})),
+        ExecuteMsg::AddCollection { collection } => add_collection(deps, env, info, collection),
+
+        ExecuteMsg::RemoveCollection { collection } => {
+            remove_collection(deps, env, info, collection)
+        }
+
+        ExecuteMsg::SetRelayer { relayer } => set_relayer(deps, env, info, relayer),
+
+        ExecuteMsg::MarkMigrated {
+            collection,
+            token_ids,
+            destination_tx,
+        } => mark_migrated(deps, env, info, collection, token_ids, destination_tx),
+
+        ExecuteMsg::SetWithdrawWindow { withdraw_window } => {
+            set_withdraw_window(deps, env, info, withdraw_window)
+        }
+
+        ExecuteMsg::ApproveOperator { operator } => approve_operator(deps, env, info, operator),
+
+        ExecuteMsg::RevokeOperator { operator } => revoke_operator(deps, env, info, operator),
+
+        ExecuteMsg::SetLockPolicy { lock_policy } => set_lock_policy(deps, env, info, lock_policy),
+
+        ExecuteMsg::SetDestinationAddress {
+            collection,
+            token_id,
+            destination_address,
+        } => set_destination_address(deps, env, info, collection, token_id, destination_address),
+
+        ExecuteMsg::DepositApproved {
+            collection,
+            token_ids,
+            destination_address,
+        } => execute_deposit_approved(deps, env, info, collection, token_ids, destination_address),
     }
 }
 
+/**
+ * Migrates the contract to the current version.
+ * The contract has to be an escrow and the new code can't be older than the stored one.
+ * Escrows deployed before the contracts were versioned get their state upgraded :
+ * the single accepted collection goes to the collections registry and the tokens are keyed by (collection, token_id)
+ * The tokens are re-keyed by batches of `limit` tokens, so that large collections don't exceed the block gas limit.
+ * While legacy tokens remain (see the legacy_tokens_left attribute), the migration has to be called again with the same code
+ * */
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    // No state migrations performed, just returned a Response
-    Ok(Response::default())
+pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from_version = if CONTRACT_INFO.may_load(deps.storage)?.is_none() {
+        migrate_legacy_state(deps.branch(), &msg)?;
+        "legacy".to_string()
+    } else {
+        let stored = get_contract_version(deps.storage)?;
+        if stored.contract != CONTRACT_NAME {
+            return Err(ContractError::WrongContract {
+                expected: CONTRACT_NAME.to_string(),
+                found: stored.contract,
+            });
+        }
+        if parse_version(&stored.version).map_err(StdError::generic_err)?
+            > parse_version(CONTRACT_VERSION).map_err(StdError::generic_err)?
+        {
+            return Err(ContractError::CannotDowngrade {
+                stored: stored.version,
+                current: CONTRACT_VERSION.to_string(),
+            });
+        }
+        stored.version
+    };
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from_version)
+        .add_attribute("to_version", CONTRACT_VERSION);
+    if let Some(collection) = LEGACY_COLLECTION.may_load(deps.storage)? {
+        let limit = msg.limit.unwrap_or(DEFAULT_MIGRATION_LIMIT) as usize;
+        let (migrated, done) = migrate_legacy_tokens(deps, &collection, limit)?;
+        response = response
+            .add_attribute("legacy_tokens_migrated", migrated.to_string())
+            .add_attribute("legacy_tokens_left", (!done).to_string());
+    }
+    Ok(response)
+}
+
+/**
+ * Upgrades the contract information of an escrow deployed before the contracts were versioned.
+ * The new contract information fields get their default values (or the ones in the migrate message)
+ * The tokens are re-keyed afterwards (see migrate_legacy_tokens)
+ * */
+fn migrate_legacy_state(deps: DepsMut, msg: &MigrateMsg) -> Result<(), ContractError> {
+    let legacy_info = LEGACY_CONTRACT_INFO.load(deps.storage)?;
+    CONTRACT_INFO.save(
+        deps.storage,
+        &ContractInfo {
+            name: legacy_info.name,
+            owner: legacy_info.owner,
+            relayer: None,
+            withdraw_window: None,
+            lock_policy: msg.lock_policy.clone().unwrap_or(LockPolicy::LockedForever),
+            destination_prefix: msg
+                .destination_prefix
+                .clone()
+                .unwrap_or_else(|| DEFAULT_DESTINATION_PREFIX.to_string()),
+        },
+    )?;
+    let collection = legacy_info.nft_address;
+    COLLECTIONS.save(deps.storage, &collection, &Empty {})?;
+    LEGACY_COLLECTION.save(deps.storage, &collection)?;
+    Ok(())
+}
+
+/**
+ * Re-keys at most `limit` legacy tokens (token_id) as (collection, token_id)
+ * Returns the number of re-keyed tokens and whether all the legacy tokens are re-keyed
+ * */
+fn migrate_legacy_tokens(
+    deps: DepsMut,
+    collection: &Addr,
+    limit: usize,
+) -> Result<(usize, bool), ContractError> {
+    // The tokens are loaded first, because the new keys are saved in the same namespace
+    let legacy_deposits = LegacyDepositNft::default();
+    let tokens: StdResult<Vec<Pair<TokenOwner>>> = legacy_deposits
+        .nfts
+        .range(
+            deps.storage,
+            Some(legacy_token_range_start()),
+            None,
+            Order::Ascending,
+        )
+        .take(limit + 1)
+        .collect();
+    let mut tokens = tokens?;
+    let done = tokens.len() <= limit;
+    tokens.truncate(limit);
+
+    let deposits = DepositNft::default();
+    for (token_id, token_owner) in &tokens {
+        let token_id = String::from_utf8(token_id.clone()).map_err(StdError::invalid_utf8)?;
+        legacy_deposits.nfts.remove(deps.storage, &token_id)?;
+        deposits
+            .nfts
+            .save(deps.storage, (collection, &token_id), token_owner)?;
+    }
+    if done {
+        LEGACY_COLLECTION.remove(deps.storage);
+    }
+    Ok((tokens.len(), done))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary> {
     match msg {
         QueryMsg::ContractInfo {} => to_binary(&contract_info(deps)?).map_err(|e| anyhow!(e)),
-        QueryMsg::RegisteredTokens { start_after, limit } => {
-            to_binary(&registered_tokens(deps, start_after, limit)?).map_err(|e| anyhow!(e))
+        QueryMsg::Collections { start_after, limit } => {
+            to_binary(&collections(deps, start_after, limit)?).map_err(|e| anyhow!(e))
         }
-        QueryMsg::Depositor { token_id } => {
-            to_binary(&depositor(deps, token_id)?).map_err(|e| anyhow!(e))
+        QueryMsg::RegisteredTokens {
+            collection,
+            start_after,
+            limit,
+        } => to_binary(&registered_tokens(deps, collection, start_after, limit)?)
+            .map_err(|e| anyhow!(e)),
+        QueryMsg::Depositor {
+            collection,
+            token_id,
+        } => to_binary(&depositor(deps, collection, token_id)?).map_err(|e| anyhow!(e)),
+        QueryMsg::DepositSnapshot {
+            collection,
+            token_id,
+        } => to_binary(&deposit_snapshot(deps, collection, token_id)?).map_err(|e| anyhow!(e)),
+        QueryMsg::Events { after_seq, limit } => {
+            to_binary(&events(deps, after_seq, limit)?).map_err(|e| anyhow!(e))
         }
         QueryMsg::UserTokens {
             user,
+            collection,
+            start_after,
+            limit,
+        } => to_binary(&user_tokens(deps, user, collection, start_after, limit)?)
+            .map_err(|e| anyhow!(e)),
+        QueryMsg::PendingTokens {
+            user,
+            collection,
+            start_after,
+            limit,
+        } => to_binary(&tokens_by_migration_status(
+            deps,
+            false,
+            user,
+            collection,
+            start_after,
+            limit,
+        )?)
+        .map_err(|e| anyhow!(e)),
+        QueryMsg::MigratedTokens {
+            user,
+            collection,
+            start_after,
+            limit,
+        } => to_binary(&tokens_by_migration_status(
+            deps,
+            true,
+            user,
+            collection,
             start_after,
             limit,
-        } => to_binary(&user_tokens(deps, user, start_after, limit)?).map_err(|e| anyhow!(e)),
+        )?)
+        .map_err(|e| anyhow!(e)),
     }
 }
 
@@ -88,8 +311,11 @@ pub fn contract_info(deps: Deps) -> Result<ContractInfoResponse> {
         .load(deps.storage)
         .map(|x| ContractInfoResponse {
             name: x.name,
-            nft_address: x.nft_address.to_string(),
             owner: x.owner.to_string(),
+            relayer: x.relayer.map(|relayer| relayer.to_string()),
+            withdraw_window: x.withdraw_window,
+            lock_policy: x.lock_policy,
+            destination_prefix: x.destination_prefix,
         })
         .map_err(|e| anyhow!(e))
 }
@@ -97,77 +323,206 @@ pub fn contract_info(deps: Deps) -> Result<ContractInfoResponse> {
 const DEFAULT_LIMIT: u32 = 10;
 const MAX_LIMIT: u32 = 1000;
 
+/**
+ * Returns the collections accepted by the escrow contract
+ * Supports pagination
+ * */
+pub fn collections(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CollectionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let collections: StdResult<Vec<String>> = COLLECTIONS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|collection| String::from_utf8(collection).map_err(StdError::invalid_utf8))
+        .collect();
+
+    Ok(CollectionsResponse {
+        collections: collections?,
+    })
+}
+
+/**
+ * Validates the optional collection filter and pagination cursor of a token query
+ * */
+fn token_query_range(
+    deps: Deps,
+    collection: Option<String>,
+    start_after: Option<TokenKey>,
+) -> StdResult<(Option<Bound>, Vec<u8>)> {
+    let collection = collection
+        .map(|collection| deps.api.addr_validate(&collection))
+        .transpose()?;
+    let start_after = start_after
+        .map(|key| -> StdResult<_> {
+            Ok(token_key(
+                &deps.api.addr_validate(&key.collection)?,
+                &key.token_id,
+            ))
+        })
+        .transpose()?;
+    Ok(token_range_start(collection.as_ref(), start_after))
+}
+
+/**
+ * Unpacks the (key + token owner) pairs as readable objects
+ * Stops at the first key outside of the queried collection (prefix).
+ * Also stops at the legacy tokens that are not re-keyed yet (see migrate), they are sorted after all the other tokens
+ * */
+fn collect_tokens(
+    pairs: impl Iterator<Item = StdResult<Pair<TokenOwner>>>,
+    prefix: &[u8],
+    limit: usize,
+) -> StdResult<Vec<TokenInfo>> {
+    pairs
+        .take_while(|item| {
+            item.as_ref()
+                .map(|(key, _)| key.starts_with(prefix) && !is_legacy_token_key(key))
+                .unwrap_or(true)
+        })
+        .take(limit)
+        .map(|item| {
+            let (key, token_owner) = item?;
+            let (collection, token_id) = parse_token_key(&key)?;
+            Ok(to_token_info(collection, token_id, token_owner))
+        })
+        .collect()
+}
+
 /**
  * Returns the deposited tokens by a specific owner address
+ * Can be restricted to one collection
  * Supports pagination
  * */
 pub fn user_tokens(
     deps: Deps,
     owner: String,
-    start_after: Option<String>,
+    collection: Option<String>,
+    start_after: Option<TokenKey>,
     limit: Option<u32>,
 ) -> StdResult<TokenInfoResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = start_after.map(Bound::exclusive);
+    let (start, prefix) = token_query_range(deps, collection, start_after)?;
 
     let owner_addr = deps.api.addr_validate(&owner)?;
-    let pks: Vec<_> = DepositNft::default()
+    let pairs = DepositNft::default()
         .nfts
         .idx
         .owner
         .prefix(owner_addr)
-        .range(deps.storage, start, None, Order::Ascending)
-        .take(limit)
-        .collect();
+        .range(deps.storage, start, None, Order::Ascending);
 
-    // We unpack the (key + result) as a readable object
-    let res: Result<Result<Vec<TokenInfo>, StdError>, _> = pks
-        .iter()
-        .map(|v| {
-            v.as_ref().map(|v| {
-                Ok(to_token_info(
-                    String::from_utf8(v.0.to_vec()).unwrap(),
-                    v.1.clone(),
-                ))
-            })
-        })
-        .collect();
-    let tokens = res.map_err(StdError::invalid_utf8)??;
+    let tokens = collect_tokens(pairs, &prefix, limit)?;
     Ok(TokenInfoResponse { tokens })
 }
 
 /**
  * Returns the deposited tokens in the contract without conditions.
+ * Can be restricted to one collection
  * Supports pagination
  * */
 pub fn registered_tokens(
     deps: Deps,
-    start_after: Option<String>,
+    collection: Option<String>,
+    start_after: Option<TokenKey>,
     limit: Option<u32>,
 ) -> StdResult<TokenInfoResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = start_after.map(Bound::exclusive);
+    let (start, prefix) = token_query_range(deps, collection, start_after)?;
 
-    let tokens: StdResult<Vec<TokenInfo>> = DepositNft::default()
+    let pairs = DepositNft::default()
         .nfts
-        .range(deps.storage, start, None, Order::Ascending)
-        .take(limit)
-        .map(|item| {
-            item.map(|(token_id, token_owner)| {
-                to_token_info(String::from_utf8(token_id.to_vec()).unwrap(), token_owner)
-            })
-        })
-        .collect();
+        .range(deps.storage, start, None, Order::Ascending);
+
+    let tokens = collect_tokens(pairs, &prefix, limit)?;
+    Ok(TokenInfoResponse { tokens })
+}
+
+/**
+ * Returns the deposited tokens that were (or were not yet) minted on the destination chain
+ * Can be restricted to one user and/or one collection
+ * Without a user, the tokens are read from the migrated index.
+ * With a user, the tokens are read from the (owner, migrated) index
+ * Supports pagination
+ * */
+pub fn tokens_by_migration_status(
+    deps: Deps,
+    migrated: bool,
+    user: Option<String>,
+    collection: Option<String>,
+    start_after: Option<TokenKey>,
+    limit: Option<u32>,
+) -> StdResult<TokenInfoResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let (start, prefix) = token_query_range(deps, collection, start_after)?;
+
+    let deposits = DepositNft::default();
+    let pairs = match user {
+        None => deposits
+            .nfts
+            .idx
+            .migrated
+            .prefix(migrated_key(migrated))
+            .range(deps.storage, start, None, Order::Ascending),
+        Some(user) => {
+            let owner_addr = deps.api.addr_validate(&user)?;
+            deposits
+                .nfts
+                .idx
+                .owner_migrated
+                .prefix((owner_addr, migrated_key(migrated)))
+                .range(deps.storage, start, None, Order::Ascending)
+        }
+    };
+    let tokens = collect_tokens(pairs, &prefix, limit)?;
+
+    Ok(TokenInfoResponse { tokens })
+}
+
+/**
+ * Returns the depositor of a specific token_id of a collection
+ * */
+pub fn depositor(deps: Deps, collection: String, token_id: String) -> StdResult<TokenInfo> {
+    let collection_addr = deps.api.addr_validate(&collection)?;
+    let depositor: TokenOwner = DepositNft::default()
+        .nfts
+        .load(deps.storage, (&collection_addr, &token_id))?;
+    Ok(to_token_info(collection, token_id, depositor))
+}
 
-    Ok(TokenInfoResponse { tokens: tokens? })
+/**
+ * Returns the metadata of a specific token_id of a collection, as it was when the token was deposited
+ * */
+pub fn deposit_snapshot(
+    deps: Deps,
+    collection: String,
+    token_id: String,
+) -> StdResult<DepositSnapshotResponse> {
+    let collection_addr = deps.api.addr_validate(&collection)?;
+    let snapshot = SNAPSHOTS.load(deps.storage, (&collection_addr, &token_id))?;
+    Ok(to_snapshot_response(collection, token_id, snapshot))
 }
 
 /**
- * Returns the depositor of a specific token_id
+ * Returns the events (deposits, withdrawals and migrations) that happened after a sequence number
+ * Allows indexers to follow the escrow without scanning all the tokens again
+ * Supports pagination
  * */
-pub fn depositor(deps: Deps, token_id: String) -> StdResult<TokenInfo> {
-    let depositor: TokenOwner = DepositNft::default().nfts.load(deps.storage, &token_id)?;
-    Ok(to_token_info(token_id, depositor))
+pub fn events(deps: Deps, after_seq: Option<u64>, limit: Option<u32>) -> StdResult<EventsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = after_seq.map(Bound::exclusive_int);
+
+    let events: StdResult<Vec<Event>> = EVENTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, event)| event))
+        .collect();
+
+    Ok(EventsResponse { events: events? })
 }
 
 /**
@@ -189,14 +544,148 @@ pub fn set_owner(deps: DepsMut, _env: Env, info: MessageInfo, owner: String) ->
         .add_attribute("value", owner))
 }
 
+/**
+ * Sets the relayer of the contract. Can only be called by the owner
+ * The relayer reports which deposited tokens were minted on the destination chain
+ * */
+pub fn set_relayer(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    relayer: String,
+) -> Result<Response> {
+    is_owner(deps.as_ref(), info.sender)?;
+
+    let relayer_addr = deps.api.addr_validate(&relayer)?;
+    CONTRACT_INFO.update::<_, StdError>(deps.storage, |mut x| {
+        x.relayer = Some(relayer_addr);
+        Ok(x)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "parameter_update")
+        .add_attribute("parameter", "relayer")
+        .add_attribute("value", relayer))
+}
+
+/**
+ * Marks deposited tokens as migrated, once they were minted on the destination chain.
+ * Can only be called by the relayer or the owner
+ * destination_tx is the hash of the destination chain transaction that minted the tokens
+ * */
+pub fn mark_migrated(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+    token_ids: Vec<String>,
+    destination_tx: String,
+) -> Result<Response> {
+    is_owner_or_relayer(deps.as_ref(), info.sender)?;
+
+    let collection_addr = deps.api.addr_validate(&collection)?;
+    let deposits = DepositNft::default();
+    let mut response = Response::new()
+        .add_attribute("action", "mark_migrated")
+        .add_attribute("collection", collection)
+        .add_attribute("destination_tx", destination_tx.clone());
+
+    for token_id in token_ids {
+        // We update the token, the migrated index is updated along with it
+        let token_owner = deposits
+            .nfts
+            .update(
+                deps.storage,
+                (&collection_addr, &token_id),
+                |token_owner| match token_owner {
+                    None => Err(ContractError::TokenNotDeposited {}),
+                    Some(token_owner) if token_owner.migrated => {
+                        Err(ContractError::AlreadyMigrated {})
+                    }
+                    Some(mut token_owner) => {
+                        token_owner.migrated = true;
+                        token_owner.migrate_time = env.block.time;
+                        token_owner.destination_tx = Some(destination_tx.clone());
+                        Ok(token_owner)
+                    }
+                },
+            )
+            .map_err(|e| anyhow!(e))?;
+        log_event(
+            deps.storage,
+            &env,
+            EventKind::MarkMigrated,
+            &collection_addr,
+            &token_id,
+            &token_owner.owner,
+        )?;
+        response = response.add_attribute("token_id", token_id);
+    }
+
+    Ok(response)
+}
+
+/**
+ * Registers a new collection the escrow accepts deposits from. Can only be called by the owner
+ * */
+pub fn add_collection(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection: String,
+) -> Result<Response> {
+    is_owner(deps.as_ref(), info.sender)?;
+
+    let collection_addr = deps.api.addr_validate(&collection)?;
+    COLLECTIONS.save(deps.storage, &collection_addr, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_collection")
+        .add_attribute("collection", collection))
+}
+
+/**
+ * Stops accepting deposits from a collection. Can only be called by the owner
+ * Tokens already deposited from this collection stay in the escrow
+ * */
+pub fn remove_collection(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection: String,
+) -> Result<Response> {
+    is_owner(deps.as_ref(), info.sender)?;
+
+    let collection_addr = deps.api.addr_validate(&collection)?;
+    COLLECTIONS.remove(deps.storage, &collection_addr);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_collection")
+        .add_attribute("collection", collection))
+}
+
+/**
+ * Verifies the destination address is a bech32 address of the destination chain
+ * It can't be validated with the api of the current chain, the address lives on another chain
+ * */
+pub fn validate_destination_address(address: &str, prefix: &str) -> Result<(), ContractError> {
+    match bech32::decode(address) {
+        Ok((hrp, _, bech32::Variant::Bech32)) if hrp == prefix => Ok(()),
+        _ => Err(ContractError::InvalidDestinationAddress {
+            prefix: prefix.to_string(),
+        }),
+    }
+}
+
 /**
  * This function receives NFTs (is called after a token was deposited in the contract using the Send NFT function).
  * The token_id should match the token_id indicated in the message
- * The nft deposited should match the NFT registered as a contract name.
- * It then saves the deposited token_id in a multiindex structure (just like NFTs)
+ * The nft deposited should come from one of the registered collections.
+ * It then saves the deposited (collection, token_id) in a multiindex structure (just like NFTs)
+ * The depositor can indicate the address that will receive the token on the destination chain
  * */
 pub fn execute_receive_nft(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     sender: String,
@@ -206,74 +695,485 @@ pub fn execute_receive_nft(
     match from_binary(&msg)? {
         ReceiveMsg::DepositNft {
             token_id: msg_token_id,
+            destination_address,
         } => {
             // We assert the message matches the sent token
             if token_id != msg_token_id {
                 return Err(anyhow!(ContractError::IncorrectTokenId {}));
             }
-            // We make sure the nft matches the contract nft
-            let contract_info = CONTRACT_INFO.load(deps.storage)?;
-            if contract_info.nft_address != info.sender {
+            // We make sure the nft comes from a registered collection
+            let collection: Addr = info.sender;
+            if !COLLECTIONS.has(deps.storage, &collection) {
                 return Err(anyhow!(ContractError::IncorrectContract {}));
             }
             // We save the token to memory
             let sender_addr = deps.api.addr_validate(&sender)?;
-
-            // We save the deposited token_id in memory to be able to retrieve it later
-            DepositNft::default().nfts.save(
-                deps.storage,
+            if let Some(destination_address) = &destination_address {
+                let contract_info = CONTRACT_INFO.load(deps.storage)?;
+                validate_destination_address(
+                    destination_address,
+                    &contract_info.destination_prefix,
+                )?;
+            }
+            save_deposit(
+                deps.branch(),
+                &env,
+                &collection,
                 &token_id,
-                &TokenOwner {
-                    owner: sender_addr,
-                    migrated: false,
-                    deposit_time: env.block.time,
-                    migrate_time: Timestamp::from_nanos(0),
-                },
+                sender_addr,
+                destination_address,
             )?;
 
             Ok(Response::new()
                 .add_attribute("action", "deposit_nft")
-                .add_attribute("address", contract_info.nft_address)
+                .add_attribute("address", collection)
                 .add_attribute("token_id", token_id)
                 .add_attribute("depositor", sender))
         }
     }
 }
 
-#[cfg(test)]
-pub mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::Api;
+/**
+ * Queries the metadata (NftInfo) of a token from its collection.
+ * The extension is decoded as a generic json value and hashed as canonical json, the same way as the minter does (see extension_hash in the minter package).
+ * The hash can then be compared with the metadata minted on the destination chain, whatever the formatting used by the collection
+ * The response is decoded with serde-json-wasm 0.4, the version used by cosmwasm-std 0.16 can't decode generic values
+ * */
+pub fn query_snapshot(
+    deps: Deps,
+    env: &Env,
+    collection: &Addr,
+    token_id: &str,
+) -> Result<DepositSnapshot> {
+    let request: QueryRequest<Empty> = QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: collection.to_string(),
+        msg: to_binary(&Cw721QueryMsg::NftInfo {
+            token_id: token_id.to_string(),
+        })?,
+    });
+    let response = match deps.querier.raw_query(&to_vec(&request)?) {
+        SystemResult::Ok(ContractResult::Ok(response)) => response,
+        _ => return Err(anyhow!(ContractError::MetadataUnavailable {})),
+    };
 
-    fn init_helper(deps: DepsMut) -> Response {
-        let instantiate_msg = InstantiateMsg {
-            name: "escrow".to_string(),
-            owner: None,
-            nft_address: "nft".to_string(),
-        };
-        let info = mock_info("creator", &[]);
-        let env = mock_env();
+    let nft_info: NftInfoResponse<JsonValue> = serde_json_wasm::from_slice(&response)
+        .map_err(|_| anyhow!(ContractError::MetadataUnavailable {}))?;
 
-        instantiate(deps, env, info, instantiate_msg).unwrap()
+    Ok(DepositSnapshot {
+        token_uri: nft_info.token_uri,
+        extension_hash: Binary::from(
+            Sha256::digest(&nft_info.extension.to_canonical_bytes()).as_slice(),
+        ),
+        snapshot_time: env.block.time,
+    })
+}
+
+/**
+ * Saves the deposited token_id in memory to be able to retrieve it later
+ * The metadata of the token is saved along with it
+ * */
+fn save_deposit(
+    deps: DepsMut,
+    env: &Env,
+    collection: &Addr,
+    token_id: &str,
+    depositor: Addr,
+    destination_address: Option<String>,
+) -> Result<()> {
+    let snapshot = query_snapshot(deps.as_ref(), env, collection, token_id)?;
+    SNAPSHOTS.save(deps.storage, (collection, token_id), &snapshot)?;
+    log_event(
+        deps.storage,
+        env,
+        EventKind::Deposit,
+        collection,
+        token_id,
+        &depositor,
+    )?;
+
+    DepositNft::default().nfts.save(
+        deps.storage,
+        (collection, token_id),
+        &TokenOwner {
+            owner: depositor,
+            migrated: false,
+            deposit_time: env.block.time,
+            migrate_time: Timestamp::from_nanos(0),
+            destination_tx: None,
+            destination_address,
+        },
+    )?;
+    Ok(())
+}
+
+/**
+ * Deposits multiple tokens of a collection in one transaction.
+ * The sender has to approve the escrow contract first (ApproveAll on the collection).
+ * The ownership of every token is verified with an OwnerOf query, then the escrow transfers the tokens to itself.
+ * If one of the transfers fails, the whole deposit is reverted
+ * */
+pub fn execute_deposit_approved(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+    token_ids: Vec<String>,
+    destination_address: Option<String>,
+) -> Result<Response> {
+    // We make sure the nfts come from a registered collection
+    let collection_addr = deps.api.addr_validate(&collection)?;
+    if !COLLECTIONS.has(deps.storage, &collection_addr) {
+        return Err(anyhow!(ContractError::IncorrectContract {}));
+    }
+    if let Some(destination_address) = &destination_address {
+        let contract_info = CONTRACT_INFO.load(deps.storage)?;
+        validate_destination_address(destination_address, &contract_info.destination_prefix)?;
     }
 
-    #[test]
-    fn test_init_sanity() {
-        let mut deps = mock_dependencies(&[]);
-        let res = init_helper(deps.as_mut());
-        assert_eq!(0, res.messages.len());
+    let mut response = Response::new()
+        .add_attribute("action", "deposit_approved")
+        .add_attribute("address", collection.clone())
+        .add_attribute("depositor", info.sender.clone());
+
+    for token_id in token_ids {
+        // We make sure the sender owns the token
+        let owner_response: OwnerOfResponse = deps.querier.query_wasm_smart(
+            collection.clone(),
+            &Cw721QueryMsg::OwnerOf {
+                token_id: token_id.clone(),
+                include_expired: None,
+            },
+        )?;
+        if owner_response.owner != info.sender {
+            return Err(anyhow!(ContractError::NotTokenOwner {}));
+        }
+
+        save_deposit(
+            deps.branch(),
+            &env,
+            &collection_addr,
+            &token_id,
+            info.sender.clone(),
+            destination_address.clone(),
+        )?;
+
+        // The escrow pulls the token, which only works if it was approved by the owner
+        let message = Cw721ExecuteMsg::TransferNft {
+            recipient: env.contract.address.to_string(),
+            token_id: token_id.clone(),
+        };
+        response = response
+            .add_message(into_cosmos_msg(message, collection.clone(), None)?)
+            .add_attribute("token_id", token_id);
     }
 
-    fn deposit_helper(
-        deps: DepsMut,
-        nft: &str,
-        token_id: &str,
-        token_id1: &str,
-    ) -> Result<Response> {
-        let env = mock_env();
-        let info = mock_info(nft, &[]);
-        execute(
+    Ok(response)
+}
+
+/**
+ * Updates the address that will receive the token on the destination chain.
+ * Can only be called by the depositor, until the token is migrated
+ * */
+pub fn set_destination_address(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection: String,
+    token_id: String,
+    destination_address: String,
+) -> Result<Response> {
+    let contract_info = CONTRACT_INFO.load(deps.storage)?;
+    validate_destination_address(&destination_address, &contract_info.destination_prefix)?;
+
+    let collection_addr = deps.api.addr_validate(&collection)?;
+    DepositNft::default()
+        .nfts
+        .update(
+            deps.storage,
+            (&collection_addr, &token_id),
+            |token_owner| match token_owner {
+                None => Err(ContractError::TokenNotDeposited {}),
+                Some(token_owner) if token_owner.owner != info.sender => {
+                    Err(ContractError::NotDepositor {})
+                }
+                Some(token_owner) if token_owner.migrated => Err(ContractError::AlreadyMigrated {}),
+                Some(mut token_owner) => {
+                    token_owner.destination_address = Some(destination_address.clone());
+                    Ok(token_owner)
+                }
+            },
+        )
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_destination_address")
+        .add_attribute("collection", collection)
+        .add_attribute("token_id", token_id)
+        .add_attribute("destination_address", destination_address))
+}
+
+/**
+ * Sets the time (in seconds) during which a token can be withdrawn after its deposit.
+ * None means the tokens can be withdrawn at any time (until they are migrated)
+ * Can only be called by the owner
+ * */
+pub fn set_withdraw_window(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    withdraw_window: Option<u64>,
+) -> Result<Response> {
+    is_owner(deps.as_ref(), info.sender)?;
+
+    CONTRACT_INFO.update::<_, StdError>(deps.storage, |mut x| {
+        x.withdraw_window = withdraw_window;
+        Ok(x)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "parameter_update")
+        .add_attribute("parameter", "withdraw_window")
+        .add_attribute(
+            "value",
+            withdraw_window
+                .map(|window| window.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ))
+}
+
+/**
+ * Sets a new lock policy. Can only be called by the owner
+ * The policy can only be tightened, so that depositors never lose the guarantees they deposited with
+ * */
+pub fn set_lock_policy(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    lock_policy: LockPolicy,
+) -> Result<Response> {
+    is_owner(deps.as_ref(), info.sender)?;
+
+    CONTRACT_INFO.update(deps.storage, |mut x| {
+        if !x.lock_policy.can_be_tightened_to(&lock_policy) {
+            return Err(anyhow!(ContractError::LockPolicyLoosened {}));
+        }
+        x.lock_policy = lock_policy.clone();
+        Ok(x)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "parameter_update")
+        .add_attribute("parameter", "lock_policy")
+        .add_attribute("value", lock_policy.to_string()))
+}
+
+/**
+ * Allows an operator to withdraw the tokens deposited by the sender
+ * */
+pub fn approve_operator(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response> {
+    let operator_addr = deps.api.addr_validate(&operator)?;
+    OPERATORS.save(deps.storage, (&info.sender, &operator_addr), &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve_operator")
+        .add_attribute("depositor", info.sender)
+        .add_attribute("operator", operator))
+}
+
+/**
+ * Removes an operator previously approved by the sender
+ * */
+pub fn revoke_operator(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response> {
+    let operator_addr = deps.api.addr_validate(&operator)?;
+    OPERATORS.remove(deps.storage, (&info.sender, &operator_addr));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_operator")
+        .add_attribute("depositor", info.sender)
+        .add_attribute("operator", operator))
+}
+
+/**
+ * Sends a deposited token back to its depositor
+ * Can only be called by the depositor (or an operator they approved)
+ * The lock policy of the contract decides if the token can still be withdrawn, migrated tokens never can.
+ * It can't be withdrawn after the withdraw window following its deposit either
+ * */
+pub fn execute_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+    token_id: String,
+) -> Result<Response> {
+    let collection_addr = deps.api.addr_validate(&collection)?;
+    let token_owner = DepositNft::default()
+        .nfts
+        .may_load(deps.storage, (&collection_addr, &token_id))?
+        .ok_or(ContractError::TokenNotDeposited {})?;
+    let depositor = token_owner.owner;
+
+    is_depositor_or_operator(deps.as_ref(), &depositor, &info.sender)?;
+
+    let contract_info = CONTRACT_INFO.load(deps.storage)?;
+    match contract_info.lock_policy {
+        LockPolicy::LockedForever => return Err(anyhow!(ContractError::TokensLocked {})),
+        LockPolicy::WithdrawableUntil { deadline } if env.block.time >= deadline => {
+            return Err(anyhow!(ContractError::WithdrawalDeadlinePassed {}))
+        }
+        _ => {}
+    }
+    // A token minted on the destination chain can't come back, whatever the policy : this would allow to double spend it
+    if token_owner.migrated {
+        return Err(anyhow!(ContractError::AlreadyMigrated {}));
+    }
+
+    if let Some(withdraw_window) = contract_info.withdraw_window {
+        if env.block.time > token_owner.deposit_time.plus_seconds(withdraw_window) {
+            return Err(anyhow!(ContractError::WithdrawalWindowClosed {}));
+        }
+    }
+
+    DepositNft::default()
+        .nfts
+        .remove(deps.storage, (&collection_addr, &token_id))?;
+    SNAPSHOTS.remove(deps.storage, (&collection_addr, &token_id));
+    log_event(
+        deps.storage,
+        &env,
+        EventKind::Withdraw,
+        &collection_addr,
+        &token_id,
+        &depositor,
+    )?;
+
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: depositor.to_string(),
+        token_id,
+    };
+
+    Ok(Response::new()
+        .add_message(into_cosmos_msg(transfer_msg, collection, None)?)
+        .add_attribute("action", "withdraw_nft")
+        .add_attribute("depositor", depositor))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{attr, from_slice, Api, OwnedDeps, Querier, QuerierResult, SystemError};
+    use cw2::ContractVersion;
+    use cw721_metadata_onchain::{Metadata, Trait};
+    use cw_storage_plus::{Index, IndexList, IndexedMap, MultiIndex};
+    use escrow_export_classic::state::LegacyContractInfo;
+    use minter_export::merkle::extension_hash;
+    use serde::{Deserialize, Serialize};
+
+    /**
+     * Answers the queries made to the nft collection
+     * The token owners are fixed, the metadata is derived from the token_id (unless nft_info is set)
+     * */
+    struct NftQuerier {
+        owners: Vec<(String, String)>,
+        nft_info: Option<String>,
+    }
+
+    impl Querier for NftQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let response = match from_slice(bin_request) {
+                Ok(QueryRequest::<Empty>::Wasm(WasmQuery::Smart { msg, .. })) => {
+                    match from_binary(&msg) {
+                        Ok(Cw721QueryMsg::OwnerOf { token_id, .. }) => self
+                            .owners
+                            .iter()
+                            .find(|(id, _)| *id == token_id)
+                            .map(|(_, owner)| {
+                                to_binary(&OwnerOfResponse {
+                                    owner: owner.clone(),
+                                    approvals: vec![],
+                                })
+                                .unwrap()
+                            }),
+                        Ok(Cw721QueryMsg::NftInfo { token_id }) => Some(Binary::from(
+                            self.nft_info
+                                .clone()
+                                .unwrap_or_else(|| {
+                                    format!(
+                                        r#"{{"token_uri":"ipfs://{0}","extension":{{"name":"{0}"}}}}"#,
+                                        token_id
+                                    )
+                                })
+                                .into_bytes(),
+                        )),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            match response {
+                Some(response) => SystemResult::Ok(ContractResult::Ok(response)),
+                None => SystemResult::Err(SystemError::InvalidRequest {
+                    error: "Unknown query".to_string(),
+                    request: bin_request.into(),
+                }),
+            }
+        }
+    }
+
+    fn mock_deps() -> OwnedDeps<MockStorage, MockApi, NftQuerier> {
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: NftQuerier {
+                owners: vec![],
+                nft_info: None,
+            },
+        }
+    }
+
+    fn init_helper(deps: DepsMut) -> Response {
+        let instantiate_msg = InstantiateMsg {
+            name: "escrow".to_string(),
+            owner: None,
+            collections: vec!["nft".to_string()],
+            relayer: Some("relayer".to_string()),
+            withdraw_window: None,
+            lock_policy: LockPolicy::WithdrawableUntilMigrated,
+            destination_prefix: "terra".to_string(),
+        };
+        let info = mock_info("creator", &[]);
+        let env = mock_env();
+
+        instantiate(deps, env, info, instantiate_msg).unwrap()
+    }
+
+    #[test]
+    fn test_init_sanity() {
+        let mut deps = mock_deps();
+        let res = init_helper(deps.as_mut());
+        assert_eq!(0, res.messages.len());
+    }
+
+    fn deposit_helper(
+        deps: DepsMut,
+        nft: &str,
+        token_id: &str,
+        token_id1: &str,
+    ) -> Result<Response> {
+        let env = mock_env();
+        let info = mock_info(nft, &[]);
+        execute(
             deps,
             env,
             info,
@@ -282,6 +1182,7 @@ pub mod tests {
                 token_id: token_id.to_string(),
                 msg: to_binary(&ReceiveMsg::DepositNft {
                     token_id: token_id1.to_string(),
+                    destination_address: None,
                 })
                 .unwrap(),
             },
@@ -290,7 +1191,7 @@ pub mod tests {
 
     #[test]
     fn test_deposit_nft() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_deps();
         init_helper(deps.as_mut());
 
         let err = deposit_helper(deps.as_mut(), "other_nft", "id", "id").unwrap_err();
@@ -312,7 +1213,7 @@ pub mod tests {
 
         let deposit = DepositNft::default()
             .nfts
-            .load(&deps.storage, "id")
+            .load(&deps.storage, (&Addr::unchecked("nft"), "id"))
             .unwrap();
         assert_eq!(deposit.owner, addr);
         assert!(!deposit.migrated);
@@ -320,7 +1221,7 @@ pub mod tests {
 
     #[test]
     fn test_deposit_multiple_nft() {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_deps();
         init_helper(deps.as_mut());
 
         deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
@@ -334,27 +1235,27 @@ pub mod tests {
 
         let deposit = DepositNft::default()
             .nfts
-            .load(&deps.storage, "id")
+            .load(&deps.storage, (&Addr::unchecked("nft"), "id"))
             .unwrap();
         assert_eq!(deposit.owner, addr);
         let deposit = DepositNft::default()
             .nfts
-            .load(&deps.storage, "id1")
+            .load(&deps.storage, (&Addr::unchecked("nft"), "id1"))
             .unwrap();
         assert_eq!(deposit.owner, addr);
         let deposit = DepositNft::default()
             .nfts
-            .load(&deps.storage, "id2")
+            .load(&deps.storage, (&Addr::unchecked("nft"), "id2"))
             .unwrap();
         assert_eq!(deposit.owner, addr);
         let deposit = DepositNft::default()
             .nfts
-            .load(&deps.storage, "id3")
+            .load(&deps.storage, (&Addr::unchecked("nft"), "id3"))
             .unwrap();
         assert_eq!(deposit.owner, addr);
         let deposit = DepositNft::default()
             .nfts
-            .load(&deps.storage, "id4")
+            .load(&deps.storage, (&Addr::unchecked("nft"), "id4"))
             .unwrap();
         assert_eq!(deposit.owner, addr);
     }
@@ -362,7 +1263,7 @@ pub mod tests {
     #[test]
     fn test_query_contract_info() {
         let env = mock_env();
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_deps();
         init_helper(deps.as_mut());
 
         let res = query(deps.as_ref(), env, QueryMsg::ContractInfo {}).unwrap();
@@ -370,8 +1271,11 @@ pub mod tests {
             from_binary::<ContractInfoResponse>(&res).unwrap(),
             ContractInfoResponse {
                 name: "escrow".to_string(),
-                nft_address: "nft".to_string(),
-                owner: "creator".to_string()
+                owner: "creator".to_string(),
+                relayer: Some("relayer".to_string()),
+                withdraw_window: None,
+                lock_policy: LockPolicy::WithdrawableUntilMigrated,
+                destination_prefix: "terra".to_string(),
             }
         )
     }
@@ -379,7 +1283,7 @@ pub mod tests {
     #[test]
     fn test_query_deposited_nft() {
         let env = mock_env();
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_deps();
         init_helper(deps.as_mut());
 
         deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
@@ -390,6 +1294,7 @@ pub mod tests {
             deps.as_ref(),
             env.clone(),
             QueryMsg::RegisteredTokens {
+                collection: None,
                 start_after: None,
                 limit: None,
             },
@@ -400,19 +1305,34 @@ pub mod tests {
             TokenInfoResponse {
                 tokens: vec![
                     TokenInfo {
+                        collection: "nft".to_string(),
                         depositor: "creator".to_string(),
                         token_id: "id".to_string(),
                         deposit_time: Timestamp::from_nanos(1571797419879305533),
+                        migrated: false,
+                        migrate_time: Timestamp::from_nanos(0),
+                        destination_tx: None,
+                        destination_address: None,
                     },
                     TokenInfo {
+                        collection: "nft".to_string(),
                         depositor: "creator".to_string(),
                         token_id: "id1".to_string(),
                         deposit_time: Timestamp::from_nanos(1571797419879305533),
+                        migrated: false,
+                        migrate_time: Timestamp::from_nanos(0),
+                        destination_tx: None,
+                        destination_address: None,
                     },
                     TokenInfo {
+                        collection: "nft".to_string(),
                         depositor: "creator".to_string(),
                         token_id: "id2".to_string(),
                         deposit_time: Timestamp::from_nanos(1571797419879305533),
+                        migrated: false,
+                        migrate_time: Timestamp::from_nanos(0),
+                        destination_tx: None,
+                        destination_address: None,
                     }
                 ]
             }
@@ -422,7 +1342,11 @@ pub mod tests {
             deps.as_ref(),
             env.clone(),
             QueryMsg::RegisteredTokens {
-                start_after: Some("id".to_string()),
+                collection: None,
+                start_after: Some(TokenKey {
+                    collection: "nft".to_string(),
+                    token_id: "id".to_string(),
+                }),
                 limit: None,
             },
         )
@@ -432,14 +1356,24 @@ pub mod tests {
             TokenInfoResponse {
                 tokens: vec![
                     TokenInfo {
+                        collection: "nft".to_string(),
                         depositor: "creator".to_string(),
                         token_id: "id1".to_string(),
                         deposit_time: Timestamp::from_nanos(1571797419879305533),
+                        migrated: false,
+                        migrate_time: Timestamp::from_nanos(0),
+                        destination_tx: None,
+                        destination_address: None,
                     },
                     TokenInfo {
+                        collection: "nft".to_string(),
                         depositor: "creator".to_string(),
                         token_id: "id2".to_string(),
                         deposit_time: Timestamp::from_nanos(1571797419879305533),
+                        migrated: false,
+                        migrate_time: Timestamp::from_nanos(0),
+                        destination_tx: None,
+                        destination_address: None,
                     }
                 ]
             }
@@ -449,7 +1383,11 @@ pub mod tests {
             deps.as_ref(),
             env,
             QueryMsg::RegisteredTokens {
-                start_after: Some("id".to_string()),
+                collection: None,
+                start_after: Some(TokenKey {
+                    collection: "nft".to_string(),
+                    token_id: "id".to_string(),
+                }),
                 limit: Some(1u32),
             },
         )
@@ -458,11 +1396,1140 @@ pub mod tests {
             from_binary::<TokenInfoResponse>(&res).unwrap(),
             TokenInfoResponse {
                 tokens: vec![TokenInfo {
+                    collection: "nft".to_string(),
                     depositor: "creator".to_string(),
                     token_id: "id1".to_string(),
                     deposit_time: Timestamp::from_nanos(1571797419879305533),
+                    migrated: false,
+                    migrate_time: Timestamp::from_nanos(0),
+                    destination_tx: None,
+                    destination_address: None,
                 }]
             }
         );
     }
+
+    #[test]
+    fn test_collections() {
+        let env = mock_env();
+        let mut deps = mock_deps();
+        init_helper(deps.as_mut());
+
+        let err = deposit_helper(deps.as_mut(), "other_nft", "id", "id").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::IncorrectContract {}
+        );
+
+        // Only the owner can register collections
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bad_person", &[]),
+            ExecuteMsg::AddCollection {
+                collection: "other_nft".to_string(),
+            },
+        )
+        .unwrap_err();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::AddCollection {
+                collection: "other_nft".to_string(),
+            },
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Collections {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            from_binary::<CollectionsResponse>(&res).unwrap(),
+            CollectionsResponse {
+                collections: vec!["nft".to_string(), "other_nft".to_string()]
+            }
+        );
+
+        // The same token_id can be deposited from two collections
+        deposit_helper(deps.as_mut(), "other_nft", "id", "id").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::RemoveCollection {
+                collection: "other_nft".to_string(),
+            },
+        )
+        .unwrap();
+        let err = deposit_helper(deps.as_mut(), "other_nft", "id1", "id1").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::IncorrectContract {}
+        );
+
+        // Tokens already deposited are still registered
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::Depositor {
+                collection: "other_nft".to_string(),
+                token_id: "id".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            from_binary::<TokenInfo>(&res).unwrap(),
+            TokenInfo {
+                collection: "other_nft".to_string(),
+                depositor: "creator".to_string(),
+                token_id: "id".to_string(),
+                deposit_time: Timestamp::from_nanos(1571797419879305533),
+                migrated: false,
+                migrate_time: Timestamp::from_nanos(0),
+                destination_tx: None,
+                destination_address: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_query_tokens_by_collection() {
+        let env = mock_env();
+        let mut deps = mock_deps();
+        init_helper(deps.as_mut());
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::AddCollection {
+                collection: "other_nft".to_string(),
+            },
+        )
+        .unwrap();
+
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+        deposit_helper(deps.as_mut(), "other_nft", "id", "id").unwrap();
+        deposit_helper(deps.as_mut(), "other_nft", "id1", "id1").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id2", "id2").unwrap();
+
+        let token_ids = |res: Binary| -> Vec<(String, String)> {
+            from_binary::<TokenInfoResponse>(&res)
+                .unwrap()
+                .tokens
+                .into_iter()
+                .map(|token| (token.collection, token.token_id))
+                .collect()
+        };
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RegisteredTokens {
+                collection: Some("other_nft".to_string()),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            token_ids(res),
+            vec![
+                ("other_nft".to_string(), "id".to_string()),
+                ("other_nft".to_string(), "id1".to_string())
+            ]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RegisteredTokens {
+                collection: None,
+                start_after: Some(TokenKey {
+                    collection: "nft".to_string(),
+                    token_id: "id2".to_string(),
+                }),
+                limit: Some(1u32),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            token_ids(res),
+            vec![("other_nft".to_string(), "id".to_string())]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::UserTokens {
+                user: "creator".to_string(),
+                collection: Some("nft".to_string()),
+                start_after: Some(TokenKey {
+                    collection: "nft".to_string(),
+                    token_id: "id".to_string(),
+                }),
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(token_ids(res), vec![("nft".to_string(), "id2".to_string())]);
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::UserTokens {
+                user: "creator".to_string(),
+                collection: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(token_ids(res).len(), 4);
+    }
+
+    fn mark_migrated_helper(deps: DepsMut, sender: &str, token_ids: &[&str]) -> Result<Response> {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::MarkMigrated {
+                collection: "nft".to_string(),
+                token_ids: token_ids.iter().map(|x| x.to_string()).collect(),
+                destination_tx: "tx_hash".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_mark_migrated() {
+        let mut deps = mock_deps();
+        init_helper(deps.as_mut());
+
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id1", "id1").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id2", "id2").unwrap();
+
+        let err = mark_migrated_helper(deps.as_mut(), "bad_person", &["id"]).unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+
+        let err = mark_migrated_helper(deps.as_mut(), "relayer", &["id3"]).unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::TokenNotDeposited {}
+        );
+
+        mark_migrated_helper(deps.as_mut(), "relayer", &["id", "id1"]).unwrap();
+        mark_migrated_helper(deps.as_mut(), "creator", &["id2"]).unwrap();
+
+        let err = mark_migrated_helper(deps.as_mut(), "relayer", &["id1"]).unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::AlreadyMigrated {}
+        );
+
+        let deposit = DepositNft::default()
+            .nfts
+            .load(&deps.storage, (&Addr::unchecked("nft"), "id"))
+            .unwrap();
+        assert!(deposit.migrated);
+        assert_eq!(deposit.migrate_time, mock_env().block.time);
+        assert_eq!(deposit.destination_tx, Some("tx_hash".to_string()));
+
+        // The migrated index is kept up to date
+        let migrated = DepositNft::default()
+            .nfts
+            .idx
+            .migrated
+            .prefix(migrated_key(true))
+            .range(&deps.storage, None, None, Order::Ascending)
+            .count();
+        assert_eq!(migrated, 3);
+        let pending = DepositNft::default()
+            .nfts
+            .idx
+            .migrated
+            .prefix(migrated_key(false))
+            .range(&deps.storage, None, None, Order::Ascending)
+            .count();
+        assert_eq!(pending, 0);
+        let user_migrated = DepositNft::default()
+            .nfts
+            .idx
+            .owner_migrated
+            .prefix((Addr::unchecked("creator"), migrated_key(true)))
+            .range(&deps.storage, None, None, Order::Ascending)
+            .count();
+        assert_eq!(user_migrated, 3);
+    }
+
+    #[test]
+    fn test_query_migration_status() {
+        let env = mock_env();
+        let mut deps = mock_deps();
+        init_helper(deps.as_mut());
+
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id1", "id1").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id2", "id2").unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("nft", &[]),
+            ExecuteMsg::ReceiveNft {
+                sender: "other_user".to_string(),
+                token_id: "id3".to_string(),
+                msg: to_binary(&ReceiveMsg::DepositNft {
+                    token_id: "id3".to_string(),
+                    destination_address: None,
+                })
+                .unwrap(),
+            },
+        )
+        .unwrap();
+        mark_migrated_helper(deps.as_mut(), "relayer", &["id1"]).unwrap();
+
+        let token_ids = |res: Binary| -> Vec<String> {
+            from_binary::<TokenInfoResponse>(&res)
+                .unwrap()
+                .tokens
+                .into_iter()
+                .map(|token| token.token_id)
+                .collect()
+        };
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PendingTokens {
+                user: None,
+                collection: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(token_ids(res), vec!["id", "id2", "id3"]);
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PendingTokens {
+                user: None,
+                collection: Some("nft".to_string()),
+                start_after: Some(TokenKey {
+                    collection: "nft".to_string(),
+                    token_id: "id".to_string(),
+                }),
+                limit: Some(1u32),
+            },
+        )
+        .unwrap();
+        assert_eq!(token_ids(res), vec!["id2"]);
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PendingTokens {
+                user: Some("other_user".to_string()),
+                collection: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(token_ids(res), vec!["id3"]);
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::MigratedTokens {
+                user: Some("creator".to_string()),
+                collection: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(token_ids(res), vec!["id1"]);
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::MigratedTokens {
+                user: Some("other_user".to_string()),
+                collection: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert!(token_ids(res).is_empty());
+    }
+
+    fn withdraw_helper(deps: DepsMut, env: Env, sender: &str, token_id: &str) -> Result<Response> {
+        execute(
+            deps,
+            env,
+            mock_info(sender, &[]),
+            ExecuteMsg::Withdraw {
+                collection: "nft".to_string(),
+                token_id: token_id.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_withdraw() {
+        let mut deps = mock_deps();
+        init_helper(deps.as_mut());
+
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id1", "id1").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id2", "id2").unwrap();
+
+        let err = withdraw_helper(deps.as_mut(), mock_env(), "creator", "id3").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::TokenNotDeposited {}
+        );
+
+        // Only the depositor can withdraw
+        let err = withdraw_helper(deps.as_mut(), mock_env(), "bad_person", "id").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::NotDepositor {}
+        );
+
+        let res = withdraw_helper(deps.as_mut(), mock_env(), "creator", "id").unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            into_cosmos_msg(
+                Cw721ExecuteMsg::TransferNft {
+                    recipient: "creator".to_string(),
+                    token_id: "id".to_string(),
+                },
+                "nft",
+                None
+            )
+            .unwrap()
+        );
+        DepositNft::default()
+            .nfts
+            .load(&deps.storage, (&Addr::unchecked("nft"), "id"))
+            .unwrap_err();
+
+        // Or an operator they approved
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::ApproveOperator {
+                operator: "operator".to_string(),
+            },
+        )
+        .unwrap();
+        withdraw_helper(deps.as_mut(), mock_env(), "operator", "id1").unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::RevokeOperator {
+                operator: "operator".to_string(),
+            },
+        )
+        .unwrap();
+        let err = withdraw_helper(deps.as_mut(), mock_env(), "operator", "id2").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::NotDepositor {}
+        );
+
+        // Migrated tokens can't be withdrawn
+        mark_migrated_helper(deps.as_mut(), "relayer", &["id2"]).unwrap();
+        let err = withdraw_helper(deps.as_mut(), mock_env(), "creator", "id2").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::AlreadyMigrated {}
+        );
+    }
+
+    #[test]
+    fn test_withdraw_window() {
+        let mut deps = mock_deps();
+        init_helper(deps.as_mut());
+
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id1", "id1").unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_person", &[]),
+            ExecuteMsg::SetWithdrawWindow {
+                withdraw_window: Some(3600),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetWithdrawWindow {
+                withdraw_window: Some(3600),
+            },
+        )
+        .unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(3600);
+        withdraw_helper(deps.as_mut(), env.clone(), "creator", "id").unwrap();
+
+        env.block.time = env.block.time.plus_seconds(1);
+        let err = withdraw_helper(deps.as_mut(), env, "creator", "id1").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::WithdrawalWindowClosed {}
+        );
+    }
+
+    fn set_lock_policy_helper(deps: DepsMut, lock_policy: LockPolicy) -> Result<Response> {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetLockPolicy { lock_policy },
+        )
+    }
+
+    #[test]
+    fn test_lock_policy() {
+        let mut deps = mock_deps();
+        init_helper(deps.as_mut());
+
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id1", "id1").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id2", "id2").unwrap();
+
+        // The equivalent policies can be swapped
+        set_lock_policy_helper(deps.as_mut(), LockPolicy::Withdrawable).unwrap();
+        set_lock_policy_helper(deps.as_mut(), LockPolicy::WithdrawableUntilMigrated).unwrap();
+
+        // The policy can only be tightened
+        let deadline = mock_env().block.time.plus_seconds(100);
+        set_lock_policy_helper(deps.as_mut(), LockPolicy::WithdrawableUntil { deadline }).unwrap();
+        let err = set_lock_policy_helper(
+            deps.as_mut(),
+            LockPolicy::WithdrawableUntil {
+                deadline: deadline.plus_seconds(1),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::LockPolicyLoosened {}
+        );
+        let err = set_lock_policy_helper(deps.as_mut(), LockPolicy::WithdrawableUntilMigrated)
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::LockPolicyLoosened {}
+        );
+        let err = set_lock_policy_helper(deps.as_mut(), LockPolicy::Withdrawable).unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::LockPolicyLoosened {}
+        );
+
+        withdraw_helper(deps.as_mut(), mock_env(), "creator", "id").unwrap();
+        let mut env = mock_env();
+        env.block.time = deadline;
+        let err = withdraw_helper(deps.as_mut(), env, "creator", "id1").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::WithdrawalDeadlinePassed {}
+        );
+
+        set_lock_policy_helper(deps.as_mut(), LockPolicy::LockedForever).unwrap();
+        let err = withdraw_helper(deps.as_mut(), mock_env(), "creator", "id2").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::TokensLocked {}
+        );
+        let err = set_lock_policy_helper(deps.as_mut(), LockPolicy::WithdrawableUntil { deadline })
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::LockPolicyLoosened {}
+        );
+    }
+
+    #[test]
+    fn test_withdrawable_migrated_token() {
+        let mut deps = mock_deps();
+        let mut instantiate_msg = InstantiateMsg {
+            name: "escrow".to_string(),
+            owner: None,
+            collections: vec!["nft".to_string()],
+            relayer: Some("relayer".to_string()),
+            withdraw_window: None,
+            lock_policy: LockPolicy::Withdrawable,
+            destination_prefix: "terra".to_string(),
+        };
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg.clone(),
+        )
+        .unwrap();
+
+        // Even with the loosest policy, migrated tokens can't be withdrawn
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id1", "id1").unwrap();
+        mark_migrated_helper(deps.as_mut(), "relayer", &["id"]).unwrap();
+        let err = withdraw_helper(deps.as_mut(), mock_env(), "creator", "id").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::AlreadyMigrated {}
+        );
+        withdraw_helper(deps.as_mut(), mock_env(), "creator", "id1").unwrap();
+
+        // With the tightest policy, nothing can be withdrawn
+        let mut deps = mock_deps();
+        instantiate_msg.lock_policy = LockPolicy::LockedForever;
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+        let err = withdraw_helper(deps.as_mut(), mock_env(), "creator", "id").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::TokensLocked {}
+        );
+    }
+
+    #[test]
+    fn test_destination_address() {
+        let mut deps = mock_deps();
+        init_helper(deps.as_mut());
+
+        let deposit_msg = |destination_address: &str| ExecuteMsg::ReceiveNft {
+            sender: "creator".to_string(),
+            token_id: "id".to_string(),
+            msg: to_binary(&ReceiveMsg::DepositNft {
+                token_id: "id".to_string(),
+                destination_address: Some(destination_address.to_string()),
+            })
+            .unwrap(),
+        };
+
+        // The address checksum is wrong
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("nft", &[]),
+            deposit_msg("terra1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd9"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidDestinationAddress {
+                prefix: "terra".to_string()
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("nft", &[]),
+            deposit_msg("terra1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd8"),
+        )
+        .unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Depositor {
+                collection: "nft".to_string(),
+                token_id: "id".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            from_binary::<TokenInfo>(&res).unwrap().destination_address,
+            Some("terra1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd8".to_string())
+        );
+
+        let set_destination_msg = |destination_address: &str| ExecuteMsg::SetDestinationAddress {
+            collection: "nft".to_string(),
+            token_id: "id".to_string(),
+            destination_address: destination_address.to_string(),
+        };
+
+        // Only the depositor can update the destination address
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_person", &[]),
+            set_destination_msg("terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::NotDepositor {}
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            set_destination_msg("terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p"),
+        )
+        .unwrap();
+        let deposit = DepositNft::default()
+            .nfts
+            .load(&deps.storage, (&Addr::unchecked("nft"), "id"))
+            .unwrap();
+        assert_eq!(
+            deposit.destination_address,
+            Some("terra15h6ndxy6zyfn6l3cjvkyj5qa9hhe005wcy9z0p".to_string())
+        );
+
+        // Until the token is migrated
+        mark_migrated_helper(deps.as_mut(), "relayer", &["id"]).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            set_destination_msg("terra1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd8"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::AlreadyMigrated {}
+        );
+    }
+
+    #[test]
+    fn test_deposit_approved() {
+        let mut deps = mock_deps();
+        deps.querier.owners = vec![
+            ("id".to_string(), "creator".to_string()),
+            ("id2".to_string(), "creator".to_string()),
+            ("id3".to_string(), "other".to_string()),
+        ];
+        init_helper(deps.as_mut());
+
+        let deposit_msg = |collection: &str, token_ids: &[&str]| ExecuteMsg::DepositApproved {
+            collection: collection.to_string(),
+            token_ids: token_ids.iter().map(|id| id.to_string()).collect(),
+            destination_address: None,
+        };
+
+        // The collection has to be registered
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            deposit_msg("other_nft", &["id"]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::IncorrectContract {}
+        );
+
+        // Every token has to be owned by the sender
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            deposit_msg("nft", &["id3"]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::NotTokenOwner {}
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            deposit_msg("nft", &["id", "id2"]),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|message| message.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                into_cosmos_msg(
+                    Cw721ExecuteMsg::TransferNft {
+                        recipient: MOCK_CONTRACT_ADDR.to_string(),
+                        token_id: "id".to_string(),
+                    },
+                    "nft",
+                    None
+                )
+                .unwrap(),
+                into_cosmos_msg(
+                    Cw721ExecuteMsg::TransferNft {
+                        recipient: MOCK_CONTRACT_ADDR.to_string(),
+                        token_id: "id2".to_string(),
+                    },
+                    "nft",
+                    None
+                )
+                .unwrap(),
+            ]
+        );
+
+        let res = user_tokens(deps.as_ref(), "creator".to_string(), None, None, None).unwrap();
+        assert_eq!(
+            res.tokens
+                .into_iter()
+                .map(|token| token.token_id)
+                .collect::<Vec<String>>(),
+            vec!["id".to_string(), "id2".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_deposit_snapshot() {
+        let mut deps = mock_deps();
+        init_helper(deps.as_mut());
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::DepositSnapshot {
+                collection: "nft".to_string(),
+                token_id: "id".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            from_binary::<DepositSnapshotResponse>(&res).unwrap(),
+            DepositSnapshotResponse {
+                collection: "nft".to_string(),
+                token_id: "id".to_string(),
+                token_uri: Some("ipfs://id".to_string()),
+                extension_hash: Binary::from(Sha256::digest(br#"{"name":"id"}"#).as_slice()),
+                snapshot_time: mock_env().block.time,
+            }
+        );
+
+        // The snapshot is removed with the deposit
+        withdraw_helper(deps.as_mut(), mock_env(), "creator", "id").unwrap();
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::DepositSnapshot {
+                collection: "nft".to_string(),
+                token_id: "id".to_string(),
+            },
+        )
+        .unwrap_err();
+
+        // Malformed metadata is refused
+        deps.querier.nft_info = Some(r#"{"token_uri":null,"extension": }"#.to_string());
+        let err = deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::MetadataUnavailable {}
+        );
+    }
+
+    #[test]
+    fn test_snapshot_matches_minter() {
+        // A cw721-metadata-onchain extension, as returned by the collection (fields in declaration order, with whitespace)
+        let mut deps = mock_deps();
+        deps.querier.nft_info = Some(
+            r#"{ "token_uri": null, "extension": {
+                "image": "ipfs://image", "image_data": null, "external_url": null,
+                "description": "A \"galactic\" punk", "name": "Punk #1",
+                "attributes": [ { "display_type": null, "trait_type": "hair", "value": "galactic glitch" } ],
+                "background_color": null, "animation_url": null, "youtube_url": null
+            } }"#
+                .to_string(),
+        );
+        init_helper(deps.as_mut());
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+        let snapshot = SNAPSHOTS
+            .load(&deps.storage, (&Addr::unchecked("nft"), "id"))
+            .unwrap();
+
+        // The same extension, as minted by the minter
+        let extension = Some(Metadata {
+            image: Some("ipfs://image".to_string()),
+            description: Some("A \"galactic\" punk".to_string()),
+            name: Some("Punk #1".to_string()),
+            attributes: Some(vec![Trait {
+                display_type: None,
+                trait_type: "hair".to_string(),
+                value: "galactic glitch".to_string(),
+            }]),
+            ..Metadata::default()
+        });
+        assert_eq!(snapshot.token_uri, None);
+        assert_eq!(
+            snapshot.extension_hash,
+            Binary::from(extension_hash(&extension).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_events() {
+        let mut deps = mock_deps();
+        init_helper(deps.as_mut());
+
+        deposit_helper(deps.as_mut(), "nft", "id", "id").unwrap();
+        deposit_helper(deps.as_mut(), "nft", "id2", "id2").unwrap();
+        mark_migrated_helper(deps.as_mut(), "relayer", &["id"]).unwrap();
+        withdraw_helper(deps.as_mut(), mock_env(), "creator", "id2").unwrap();
+
+        let res = events(deps.as_ref(), None, None).unwrap();
+        assert_eq!(
+            res.events
+                .iter()
+                .map(|event| (event.seq, event.kind.clone(), event.token_id.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, EventKind::Deposit, "id"),
+                (2, EventKind::Deposit, "id2"),
+                (3, EventKind::MarkMigrated, "id"),
+                (4, EventKind::Withdraw, "id2"),
+            ]
+        );
+        assert_eq!(
+            res.events[2],
+            Event {
+                seq: 3,
+                kind: EventKind::MarkMigrated,
+                collection: Addr::unchecked("nft"),
+                token_id: "id".to_string(),
+                depositor: Addr::unchecked("creator"),
+                time: mock_env().block.time,
+            }
+        );
+
+        // Indexers only get the events after their last sync
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Events {
+                after_seq: Some(2),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let res: EventsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.events.iter().map(|event| event.seq).collect::<Vec<_>>(),
+            vec![3]
+        );
+        let res = events(deps.as_ref(), Some(4), None).unwrap();
+        assert_eq!(res.events, vec![]);
+    }
+
+    /**
+     * Token of an escrow deployed before the contracts were versioned, as it was saved (no destination fields)
+     * Only the owner index was written
+     * */
+    #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+    struct LegacyTokenOwner {
+        owner: Addr,
+        migrated: bool,
+        deposit_time: Timestamp,
+        migrate_time: Timestamp,
+    }
+
+    struct LegacyTokenIndexes<'a> {
+        owner: MultiIndex<'a, (Addr, Vec<u8>), LegacyTokenOwner>,
+    }
+
+    impl<'a> IndexList<LegacyTokenOwner> for LegacyTokenIndexes<'a> {
+        fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<LegacyTokenOwner>> + '_> {
+            let v: Vec<&dyn Index<LegacyTokenOwner>> = vec![&self.owner];
+            Box::new(v.into_iter())
+        }
+    }
+
+    #[test]
+    fn test_migrate() {
+        let mut deps = mock_deps();
+
+        // State of an escrow deployed before the contracts were versioned
+        LEGACY_CONTRACT_INFO
+            .save(
+                &mut deps.storage,
+                &LegacyContractInfo {
+                    name: "escrow".to_string(),
+                    nft_address: Addr::unchecked("nft"),
+                    owner: Addr::unchecked("creator"),
+                },
+            )
+            .unwrap();
+        let legacy_tokens: IndexedMap<&str, LegacyTokenOwner, LegacyTokenIndexes> = IndexedMap::new(
+            "tokens",
+            LegacyTokenIndexes {
+                owner: MultiIndex::new(
+                    |d: &LegacyTokenOwner, k| (d.owner.clone(), k),
+                    "tokens",
+                    "tokens__owner",
+                ),
+            },
+        );
+        for (token_id, migrated) in [("id", false), ("id2", true), ("id3", false)] {
+            legacy_tokens
+                .save(
+                    &mut deps.storage,
+                    token_id,
+                    &LegacyTokenOwner {
+                        owner: Addr::unchecked("creator"),
+                        migrated,
+                        deposit_time: mock_env().block.time,
+                        migrate_time: Timestamp::from_nanos(0),
+                    },
+                )
+                .unwrap();
+        }
+
+        // The tokens are re-keyed by batches
+        let migrate_msg = MigrateMsg {
+            limit: Some(2),
+            ..MigrateMsg::default()
+        };
+        let res = migrate(deps.as_mut(), mock_env(), migrate_msg.clone()).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "migrate"),
+                attr("from_version", "legacy"),
+                attr("to_version", CONTRACT_VERSION),
+                attr("legacy_tokens_migrated", "2"),
+                attr("legacy_tokens_left", "true"),
+            ]
+        );
+
+        let res = contract_info(deps.as_ref()).unwrap();
+        assert_eq!(res.owner, "creator".to_string());
+        assert_eq!(res.lock_policy, LockPolicy::LockedForever);
+        assert_eq!(res.destination_prefix, "terra".to_string());
+        let res = collections(deps.as_ref(), None, None).unwrap();
+        assert_eq!(res.collections, vec!["nft".to_string()]);
+
+        // Until the migration is done, the tokens left are not returned by the token queries
+        let token_ids = |res: TokenInfoResponse| {
+            res.tokens
+                .into_iter()
+                .map(|token| token.token_id)
+                .collect::<Vec<_>>()
+        };
+        let res = registered_tokens(deps.as_ref(), None, None, None).unwrap();
+        assert_eq!(token_ids(res), vec!["id".to_string(), "id2".to_string()]);
+        let res = user_tokens(deps.as_ref(), "creator".to_string(), None, None, None).unwrap();
+        assert_eq!(token_ids(res), vec!["id".to_string(), "id2".to_string()]);
+        let res = tokens_by_migration_status(
+            deps.as_ref(),
+            false,
+            Some("creator".to_string()),
+            Some("nft".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(token_ids(res), vec!["id".to_string()]);
+        assert_eq!(
+            get_contract_version(&deps.storage).unwrap(),
+            ContractVersion {
+                contract: CONTRACT_NAME.to_string(),
+                version: CONTRACT_VERSION.to_string(),
+            }
+        );
+
+        // The migration goes on with the same code
+        let res = migrate(deps.as_mut(), mock_env(), migrate_msg.clone()).unwrap();
+        assert_eq!(res.attributes[1], attr("from_version", CONTRACT_VERSION));
+        assert_eq!(res.attributes[3], attr("legacy_tokens_migrated", "1"));
+        assert_eq!(res.attributes[4], attr("legacy_tokens_left", "false"));
+        assert_eq!(LEGACY_COLLECTION.may_load(&deps.storage).unwrap(), None);
+
+        // The tokens are keyed by (collection, token_id), the indexes are rebuilt
+        let res = user_tokens(deps.as_ref(), "creator".to_string(), None, None, None).unwrap();
+        assert_eq!(
+            res.tokens
+                .into_iter()
+                .map(|token| (token.collection, token.token_id, token.destination_address))
+                .collect::<Vec<_>>(),
+            vec![
+                ("nft".to_string(), "id".to_string(), None),
+                ("nft".to_string(), "id2".to_string(), None),
+                ("nft".to_string(), "id3".to_string(), None)
+            ]
+        );
+        let res = tokens_by_migration_status(deps.as_ref(), true, None, None, None, None).unwrap();
+        assert_eq!(res.tokens.len(), 1);
+        assert_eq!(res.tokens[0].token_id, "id2".to_string());
+        let res = tokens_by_migration_status(
+            deps.as_ref(),
+            false,
+            Some("creator".to_string()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.tokens.len(), 2);
+        assert_eq!(legacy_tokens.may_load(&deps.storage, "id").unwrap(), None);
+
+        // Migrating to the same version is allowed
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(res.attributes.len(), 3);
+
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::CannotDowngrade {
+                stored: "99.0.0".to_string(),
+                current: CONTRACT_VERSION.to_string(),
+            }
+        );
+
+        // Pre-releases come before their release
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.0.1-beta.1").unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+        set_contract_version(&mut deps.storage, "crates.io:minter", "0.0.1").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::WrongContract {
+                expected: CONTRACT_NAME.to_string(),
+                found: "crates.io:minter".to_string(),
+            }
+        );
+    }
 }
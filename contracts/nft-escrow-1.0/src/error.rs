@@ -17,4 +17,37 @@ pub enum ContractError {
 
     #[error("Token not deposited in the contract yet")]
     TokenNotDeposited {},
+
+    #[error("Token already marked as migrated")]
+    AlreadyMigrated {},
+
+    #[error("Only the depositor or an operator approved by the depositor can withdraw this token")]
+    NotDepositor {},
+
+    #[error("The withdrawal window of this token is closed")]
+    WithdrawalWindowClosed {},
+
+    #[error("Tokens are locked forever in this escrow contract")]
+    TokensLocked {},
+
+    #[error("The withdrawal deadline has passed")]
+    WithdrawalDeadlinePassed {},
+
+    #[error("The lock policy can only be tightened")]
+    LockPolicyLoosened {},
+
+    #[error("Destination address is not a valid {prefix} address")]
+    InvalidDestinationAddress { prefix: String },
+
+    #[error("Only the owner of a token can deposit it")]
+    NotTokenOwner {},
+
+    #[error("The metadata of the token could not be read from its collection")]
+    MetadataUnavailable {},
+
+    #[error("Cannot migrate a {found} contract to {expected}")]
+    WrongContract { expected: String, found: String },
+
+    #[error("Cannot migrate from version {stored} to the older version {current}")]
+    CannotDowngrade { stored: String, current: String },
 }
@@ -1,30 +1,130 @@
 use crate::error::ContractError;
-use cosmwasm_std::{Addr, Deps};
-use cw_storage_plus::{IndexedMap, Item, MultiIndex};
-use escrow_export_classic::state::{ContractInfo, TokenIndexes, TokenOwner};
+use cosmwasm_std::{Addr, Deps, Empty, Env, StdError, StdResult, Storage};
+use cw_storage_plus::{Bound, IndexedMap, Item, Map, MultiIndex, PrimaryKey, U64Key, U8Key};
+use escrow_export_classic::state::{
+    ContractInfo, DepositSnapshot, Event, EventKind, LegacyContractInfo, TokenIndexes, TokenOwner,
+};
 
-pub const CONTRACT_INFO: Item<ContractInfo> = Item::new("contract_info");
+/**
+ * The contract information moved away from the "contract_info" key, which holds the contract version (cw2)
+ * */
+pub const CONTRACT_INFO: Item<ContractInfo> = Item::new("escrow_info");
+
+/**
+ * Contract information of the escrows deployed before the contracts were versioned
+ * Only read when migrating them
+ * */
+pub const LEGACY_CONTRACT_INFO: Item<LegacyContractInfo> = Item::new("contract_info");
+
+/**
+ * Collection of the legacy tokens, saved while some of them still have to be re-keyed by the migration
+ * */
+pub const LEGACY_COLLECTION: Item<Addr> = Item::new("legacy_collection");
+
+/**
+ * Registry of the NFT collections the escrow accepts deposits from
+ * */
+pub const COLLECTIONS: Map<&Addr, Empty> = Map::new("collections");
+
+/**
+ * Operators allowed to withdraw tokens on behalf of a depositor, keyed by (depositor, operator)
+ * */
+pub const OPERATORS: Map<(&Addr, &Addr), Empty> = Map::new("operators");
+
+/**
+ * Metadata of the deposited tokens, keyed by (collection, token_id)
+ * */
+pub const SNAPSHOTS: Map<(&Addr, &str), DepositSnapshot> = Map::new("snapshots");
+
+/**
+ * Log of the deposits, withdrawals and migrations, keyed by sequence number
+ * EVENT_SEQ holds the sequence number of the last event
+ * */
+pub const EVENTS: Map<U64Key, Event> = Map::new("events");
+pub const EVENT_SEQ: Item<u64> = Item::new("event_seq");
+
+/**
+ * Appends an event to the log and returns its sequence number
+ * */
+pub fn log_event(
+    storage: &mut dyn Storage,
+    env: &Env,
+    kind: EventKind,
+    collection: &Addr,
+    token_id: &str,
+    depositor: &Addr,
+) -> StdResult<u64> {
+    let seq = EVENT_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    EVENT_SEQ.save(storage, &seq)?;
+    EVENTS.save(
+        storage,
+        U64Key::new(seq),
+        &Event {
+            seq,
+            kind,
+            collection: collection.clone(),
+            token_id: token_id.to_string(),
+            depositor: depositor.clone(),
+            time: env.block.time,
+        },
+    )?;
+    Ok(seq)
+}
 
 pub fn token_owner_idx(d: &TokenOwner, k: Vec<u8>) -> (Addr, Vec<u8>) {
     (d.owner.clone(), k)
 }
 
-pub fn migrated_idx(d: &TokenOwner, k: Vec<u8>) -> (bool, Vec<u8>) {
-    (d.migrated, k)
+pub fn migrated_idx(d: &TokenOwner, k: Vec<u8>) -> (U8Key, Vec<u8>) {
+    (migrated_key(d.migrated), k)
+}
+
+pub fn owner_migrated_idx(d: &TokenOwner, k: Vec<u8>) -> (Addr, U8Key, Vec<u8>) {
+    (d.owner.clone(), migrated_key(d.migrated), k)
+}
+
+/**
+ * Booleans can't be used as storage keys, the migrated index stores them as a single byte
+ * */
+pub fn migrated_key(migrated: bool) -> U8Key {
+    U8Key::new(migrated as u8)
 }
 
 pub struct DepositNft<'a> {
-    pub nfts: IndexedMap<'a, &'a str, TokenOwner, TokenIndexes<'a>>,
+    pub nfts: IndexedMap<'a, (&'a Addr, &'a str), TokenOwner, TokenIndexes<'a>>,
 }
 
 /**
  * Structure used to save tokens and be able to query them by owner
+ * Tokens are keyed by (collection, token_id)
  * */
 impl Default for DepositNft<'_> {
     fn default() -> Self {
         let indexes: TokenIndexes = TokenIndexes {
             owner: MultiIndex::new(token_owner_idx, "tokens", "tokens__owner"),
             migrated: MultiIndex::new(migrated_idx, "tokens", "tokens__migrated"),
+            owner_migrated: MultiIndex::new(owner_migrated_idx, "tokens", "tokens__owner_migrated"),
+        };
+        Self {
+            nfts: IndexedMap::new("tokens", indexes),
+        }
+    }
+}
+
+pub struct LegacyDepositNft<'a> {
+    pub nfts: IndexedMap<'a, &'a str, TokenOwner, TokenIndexes<'a>>,
+}
+
+/**
+ * Tokens deposited before the escrow accepted multiple collections, keyed by token_id only
+ * The indexes are shared with DepositNft, only the primary key differs
+ * */
+impl Default for LegacyDepositNft<'_> {
+    fn default() -> Self {
+        let indexes: TokenIndexes = TokenIndexes {
+            owner: MultiIndex::new(token_owner_idx, "tokens", "tokens__owner"),
+            migrated: MultiIndex::new(migrated_idx, "tokens", "tokens__migrated"),
+            owner_migrated: MultiIndex::new(owner_migrated_idx, "tokens", "tokens__owner_migrated"),
         };
         Self {
             nfts: IndexedMap::new("tokens", indexes),
@@ -32,6 +132,65 @@ impl Default for DepositNft<'_> {
     }
 }
 
+/**
+ * Lower bound of the legacy tokens, in the namespace they share with the DepositNft tokens
+ * DepositNft keys start with the length of the collection address on 2 bytes, so their first byte is 0.
+ * Legacy keys are token ids, which start with a printable character
+ * */
+pub fn legacy_token_range_start() -> Bound {
+    Bound::inclusive(vec![1u8])
+}
+
+/**
+ * Returns true for the keys of the legacy tokens that were not re-keyed yet (see legacy_token_range_start)
+ * */
+pub fn is_legacy_token_key(key: &[u8]) -> bool {
+    key.first().map_or(false, |first| *first != 0)
+}
+
+/**
+ * Returns the storage key of a deposited token, as found in the DepositNft indexes
+ * */
+pub fn token_key(collection: &Addr, token_id: &str) -> Vec<u8> {
+    (collection, token_id).joined_key()
+}
+
+/**
+ * Splits a DepositNft key back into its (collection, token_id) parts
+ * The collection is length-prefixed (2 bytes, big endian), the token_id takes the rest of the key
+ * */
+pub fn parse_token_key(key: &[u8]) -> StdResult<(String, String)> {
+    if key.len() < 2 {
+        return Err(StdError::generic_err("Invalid token key"));
+    }
+    let collection_len = u16::from_be_bytes([key[0], key[1]]) as usize;
+    if key.len() < 2 + collection_len {
+        return Err(StdError::generic_err("Invalid token key"));
+    }
+    let collection = String::from_utf8(key[2..2 + collection_len].to_vec())?;
+    let token_id = String::from_utf8(key[2 + collection_len..].to_vec())?;
+    Ok((collection, token_id))
+}
+
+/**
+ * Computes the lower bound of a DepositNft range and the key prefix every returned key should have
+ * when the results are restricted to one collection
+ * */
+pub fn token_range_start(
+    collection: Option<&Addr>,
+    start_after: Option<Vec<u8>>,
+) -> (Option<Bound>, Vec<u8>) {
+    let prefix = collection
+        .map(|collection| token_key(collection, ""))
+        .unwrap_or_default();
+    let start = match start_after {
+        Some(key) if key >= prefix => Some(Bound::exclusive(key)),
+        _ if !prefix.is_empty() => Some(Bound::inclusive(prefix.clone())),
+        _ => None,
+    };
+    (start, prefix)
+}
+
 pub fn is_owner(deps: Deps, addr: Addr) -> Result<(), ContractError> {
     if CONTRACT_INFO.load(deps.storage)?.owner == addr {
         Ok(())
@@ -39,3 +198,30 @@ pub fn is_owner(deps: Deps, addr: Addr) -> Result<(), ContractError> {
         Err(ContractError::Unauthorized {})
     }
 }
+
+/**
+ * Operators approved by a depositor can act on their tokens
+ * */
+pub fn is_depositor_or_operator(
+    deps: Deps,
+    depositor: &Addr,
+    addr: &Addr,
+) -> Result<(), ContractError> {
+    if depositor == addr || OPERATORS.has(deps.storage, (depositor, addr)) {
+        Ok(())
+    } else {
+        Err(ContractError::NotDepositor {})
+    }
+}
+
+/**
+ * The relayer reports the tokens minted on the destination chain. The owner can always do it as well
+ * */
+pub fn is_owner_or_relayer(deps: Deps, addr: Addr) -> Result<(), ContractError> {
+    let contract_info = CONTRACT_INFO.load(deps.storage)?;
+    if contract_info.owner == addr || contract_info.relayer == Some(addr) {
+        Ok(())
+    } else {
+        Err(ContractError::Unauthorized {})
+    }
+}
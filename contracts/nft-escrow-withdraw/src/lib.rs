@@ -1,3 +0,0 @@
-pub mod contract;
-pub mod error;
-pub mod state;
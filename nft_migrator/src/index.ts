@@ -6,7 +6,9 @@ import https from 'https';
 import fs from 'fs';
 import toobusy from 'toobusy-js';
 import { Address } from './terra_utils';
-import { LCDClient, Wallet } from '@terra-money/terra.js';
+import { LCDClient } from '@terra-money/terra.js';
+import { env } from './env_helper';
+import { MintMsg, newMintRequest, signRedeemRequest } from './signing';
 
 let globalEnv = require('../env.json');
 const PORT = 8081;
@@ -36,35 +38,12 @@ app.use(function (_req, res, next) {
   }
 });
 
-interface MintRequest {
-  mint_msg: MintMsg;
-  nft_contract: String;
-}
-
-interface MintMsg {
-  token_id: String;
-  owner: String;
-  token_uri: String | undefined;
-  extension: any;
-}
 interface Attribute {
   display_type: string | null;
   trait_type: string;
   value: string;
 }
 
-export async function signRedeemRequest(
-  data: MintRequest,
-  wallet: Wallet
-): Promise<string> {
-  // First we parse the request into a metadata message :
-
-  const requestBuffer = Buffer.from(JSON.stringify(data));
-  const signed = await wallet.key.sign(requestBuffer);
-  console.log(wallet.key.publicKey);
-  return signed.toString('base64');
-}
-
 let terra_classic = new LCDClient(globalEnv['classic']['chain']);
 
 function regular_metadata(tokenId: string, metadata: any): [string, any] {
@@ -168,17 +147,19 @@ async function getTokenMintMessage(
     extension: newTokenMetadata.extension ?? null
   };
 
-  let mintRequest: MintRequest = {
-    mint_msg: mintMsg,
-    nft_contract: nftAddress2
-  };
-
-  let signature = await signRedeemRequest(mintRequest, minter);
+  // The request is bound to the Terra 2.0 chain and to the minter contract of the collection
+  let mintRequest = newMintRequest(mintMsg, nftAddress2);
+  let signature = await signRedeemRequest(
+    env['chain']['chainID'],
+    contractInfo.minter_contract,
+    mintRequest,
+    minter
+  );
 
   let mintExecuteMsg = {
     mint_request: mintRequest,
-    signature,
-    minter: contractInfo.minter
+    signatures: [signature],
+    minter: contractInfo.minter_contract
   };
   return mintExecuteMsg;
 }
@@ -210,6 +191,7 @@ async function main() {
       terra_classic.wasm
         .contractQuery(contractInfo.escrow_contract, {
           depositor: {
+            collection: contractInfo.contract1,
             token_id: tokenId
           }
         })
@@ -274,6 +256,7 @@ async function main() {
       await terra_classic.wasm
         .contractQuery(contractInfo.escrow_contract, {
           depositor: {
+            collection: contractInfo.contract1,
             token_id: lootopianId
           }
         })
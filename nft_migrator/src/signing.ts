@@ -0,0 +1,154 @@
+import crypto from 'crypto';
+import { Wallet } from '@terra-money/terra.js';
+
+// Mint requests are only valid for this long after they were signed
+const MINT_REQUEST_VALIDITY_MS = 60 * 60 * 1000;
+
+export interface MintMsg {
+  token_id: string;
+  owner: string;
+  token_uri: string | null;
+  extension: any;
+}
+
+export type Expiration =
+  | { at_height: number }
+  | { at_time: string }
+  | { never: {} };
+
+export interface MintRequest {
+  mint_msg: MintMsg;
+  nft_contract: string;
+  // Uint64, encoded as a string
+  nonce: string;
+  expires_at: Expiration;
+}
+
+export interface MintSignature {
+  key_id: string | null;
+  signature: string;
+  adr036_signer: string | null;
+}
+
+/// Builds a mint request that expires after MINT_REQUEST_VALIDITY_MS, with a random nonce
+export function newMintRequest(
+  mintMsg: MintMsg,
+  nftContract: string
+): MintRequest {
+  let expiresAtMs = Date.now() + MINT_REQUEST_VALIDITY_MS;
+  return {
+    mint_msg: mintMsg,
+    nft_contract: nftContract,
+    // randomInt only accepts ranges smaller than 2^48
+    nonce: crypto.randomInt(2 ** 48 - 1).toString(),
+    // Timestamps are encoded as nanoseconds
+    expires_at: { at_time: `${expiresAtMs}000000` }
+  };
+}
+
+/// Writes a json string, escaped the same way as the contract (serde-json-wasm)
+function canonicalString(value: string): string {
+  let escaped = '';
+  for (const c of value) {
+    switch (c) {
+      case '\\':
+        escaped += '\\\\';
+        break;
+      case '"':
+        escaped += '\\"';
+        break;
+      case '\b':
+        escaped += '\\b';
+        break;
+      case '\t':
+        escaped += '\\t';
+        break;
+      case '\n':
+        escaped += '\\n';
+        break;
+      case '\f':
+        escaped += '\\f';
+        break;
+      case '\r':
+        escaped += '\\r';
+        break;
+      default: {
+        let code = c.codePointAt(0) ?? 0;
+        if (code < 0x20) {
+          escaped += '\\u' + code.toString(16).toUpperCase().padStart(4, '0');
+        } else {
+          escaped += c;
+        }
+      }
+    }
+  }
+  return `"${escaped}"`;
+}
+
+/// Encodes a value as canonical json, the encoding the contract verifies signatures against :
+///  - object keys are sorted (byte order) at every level
+///  - no whitespace
+///  - undefined and null values are encoded as null, they are never omitted
+///  - numbers are integers
+///  - strings are utf-8, only quotes, backslashes and control characters are escaped
+export function canonicalJson(value: any): string {
+  if (value === null || value === undefined) {
+    return 'null';
+  }
+  if (typeof value === 'boolean') {
+    return value ? 'true' : 'false';
+  }
+  if (typeof value === 'number') {
+    if (!Number.isSafeInteger(value)) {
+      throw Error(`Only integers can be signed, got ${value}`);
+    }
+    return value.toString();
+  }
+  if (typeof value === 'string') {
+    return canonicalString(value);
+  }
+  if (Array.isArray(value)) {
+    return `[${value.map(canonicalJson).join(',')}]`;
+  }
+  let keys = Object.keys(value).sort((a, b) =>
+    Buffer.compare(Buffer.from(a), Buffer.from(b))
+  );
+  let fields = keys.map(
+    (key) => `${canonicalString(key)}:${canonicalJson(value[key])}`
+  );
+  return `{${fields.join(',')}}`;
+}
+
+/// Returns the exact bytes the minter authority signs for a mint request (signing_bytes in the minter package)
+/// The request is bound to a chain and to a minter contract
+export function signingBytes(
+  chainId: string,
+  minterContract: string,
+  mintRequest: MintRequest
+): Buffer {
+  return Buffer.from(
+    canonicalJson({
+      chain_id: chainId,
+      minter_contract: minterContract,
+      mint_request: mintRequest
+    })
+  );
+}
+
+/// Signs a mint request with the minter wallet.
+/// The wallet key signs the sha256 hash of the signing bytes, which is what the contract verifies
+export async function signRedeemRequest(
+  chainId: string,
+  minterContract: string,
+  mintRequest: MintRequest,
+  wallet: Wallet
+): Promise<MintSignature> {
+  const signed = await wallet.key.sign(
+    signingBytes(chainId, minterContract, mintRequest)
+  );
+  return {
+    key_id: null,
+    signature: signed.toString('base64'),
+    adr036_signer: null
+  };
+}
@@ -0,0 +1,55 @@
+import crypto from 'crypto';
+import { newMintRequest, signingBytes } from '../signing';
+
+/// Checks the server signs the same bytes as the ones the minter contract verifies
+/// The vectors are generated from the contract encoding (packages/minter/testdata/signing_vectors.json)
+function main() {
+  let vectors = require('../../../packages/minter/testdata/signing_vectors.json');
+  for (const vector of vectors) {
+    let bytes = signingBytes(
+      vector.chain_id,
+      vector.minter_contract,
+      vector.mint_request
+    );
+    if (bytes.toString() != vector.signing_bytes) {
+      throw Error(
+        `${vector.description}: signing bytes don't match\n${bytes.toString()}\n${vector.signing_bytes}`
+      );
+    }
+    let hash = crypto.createHash('sha256').update(bytes).digest('base64');
+    if (hash != vector.sha256) {
+      throw Error(`${vector.description}: sha256 doesn't match`);
+    }
+  }
+  console.log(`${vectors.length} signing vectors ok`);
+
+  // The requests built by the server only differ from the first vector by their nonce and expiration
+  let vector = vectors[0];
+  let mintRequest = newMintRequest(
+    vector.mint_request.mint_msg,
+    vector.mint_request.nft_contract
+  );
+  if (!/^[0-9]+$/.test(mintRequest.nonce)) {
+    throw Error(`Invalid nonce ${mintRequest.nonce}`);
+  }
+  let expiresAt = (mintRequest.expires_at as { at_time: string }).at_time;
+  if (!/^[0-9]+$/.test(expiresAt) || Number(expiresAt) <= Date.now() * 1e6) {
+    throw Error(`Invalid expiration ${expiresAt}`);
+  }
+  let expected = vector.signing_bytes
+    .replace('{"at_height":4200000}', `{"at_time":"${expiresAt}"}`)
+    .replace('"nonce":"1"', `"nonce":"${mintRequest.nonce}"`);
+  let bytes = signingBytes(
+    vector.chain_id,
+    vector.minter_contract,
+    mintRequest
+  );
+  if (bytes.toString() != expected) {
+    throw Error(
+      `New mint request: signing bytes don't match\n${bytes.toString()}\n${expected}`
+    );
+  }
+  console.log('new mint request ok');
+}
+
+main();
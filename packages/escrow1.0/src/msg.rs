@@ -1,4 +1,4 @@
-use crate::state::TokenOwner;
+use crate::state::{DepositSnapshot, Event, LockPolicy, TokenOwner};
 use cosmwasm_std::{to_binary, Binary, Coin, CosmosMsg, StdError, StdResult, Timestamp, WasmMsg};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -21,14 +21,26 @@ pub fn into_cosmos_msg<M: Serialize, T: Into<String>>(
     Ok(execute.into())
 }
 
-#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
-pub struct MigrateMsg {}
+/**
+ * The parameters are only used when migrating an escrow deployed before the contracts were versioned.
+ * Default : locked_forever, the terra prefix and 200 tokens re-keyed per migration (limit)
+ * */
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct MigrateMsg {
+    pub lock_policy: Option<LockPolicy>,
+    pub destination_prefix: Option<String>,
+    pub limit: Option<u32>,
+}
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct InstantiateMsg {
     pub name: String,
-    pub nft_address: String,
+    pub collections: Vec<String>,
     pub owner: Option<String>,
+    pub relayer: Option<String>,
+    pub withdraw_window: Option<u64>,
+    pub lock_policy: LockPolicy,
+    pub destination_prefix: String,
 }
 
 pub fn is_valid_name(name: &str) -> bool {
@@ -39,19 +51,29 @@ pub fn is_valid_name(name: &str) -> bool {
     true
 }
 
-pub fn to_token_info(token_id: String, token_owner: TokenOwner) -> TokenInfo {
+pub fn to_token_info(collection: String, token_id: String, token_owner: TokenOwner) -> TokenInfo {
     TokenInfo {
+        collection,
         token_id,
         depositor: token_owner.owner.to_string(),
         deposit_time: token_owner.deposit_time,
+        migrated: token_owner.migrated,
+        migrate_time: token_owner.migrate_time,
+        destination_tx: token_owner.destination_tx,
+        destination_address: token_owner.destination_address,
     }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct TokenInfo {
+    pub collection: String,
     pub token_id: String,
     pub depositor: String,
     pub deposit_time: Timestamp,
+    pub migrated: bool,
+    pub migrate_time: Timestamp,
+    pub destination_tx: Option<String>,
+    pub destination_address: Option<String>,
 }
 
 impl InstantiateMsg {
@@ -75,42 +97,127 @@ pub enum ExecuteMsg {
         msg: Binary,
     },
     Withdraw {
+        collection: String,
         token_id: String,
     },
     SetOwner {
         owner: String,
     },
+    AddCollection {
+        collection: String,
+    },
+    RemoveCollection {
+        collection: String,
+    },
+    SetRelayer {
+        relayer: String,
+    },
+    MarkMigrated {
+        collection: String,
+        token_ids: Vec<String>,
+        destination_tx: String,
+    },
+    SetWithdrawWindow {
+        withdraw_window: Option<u64>,
+    },
+    ApproveOperator {
+        operator: String,
+    },
+    RevokeOperator {
+        operator: String,
+    },
+    SetLockPolicy {
+        lock_policy: LockPolicy,
+    },
+    SetDestinationAddress {
+        collection: String,
+        token_id: String,
+        destination_address: String,
+    },
+    DepositApproved {
+        collection: String,
+        token_ids: Vec<String>,
+        destination_address: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     ContractInfo {},
-    RegisteredTokens {
+    Collections {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    RegisteredTokens {
+        collection: Option<String>,
+        start_after: Option<TokenKey>,
+        limit: Option<u32>,
+    },
     UserTokens {
         user: String,
-        start_after: Option<String>,
+        collection: Option<String>,
+        start_after: Option<TokenKey>,
         limit: Option<u32>,
     },
     Depositor {
+        collection: String,
         token_id: String,
     },
+    DepositSnapshot {
+        collection: String,
+        token_id: String,
+    },
+    Events {
+        after_seq: Option<u64>,
+        limit: Option<u32>,
+    },
+    PendingTokens {
+        user: Option<String>,
+        collection: Option<String>,
+        start_after: Option<TokenKey>,
+        limit: Option<u32>,
+    },
+    MigratedTokens {
+        user: Option<String>,
+        collection: Option<String>,
+        start_after: Option<TokenKey>,
+        limit: Option<u32>,
+    },
+}
+
+/**
+ * Identifies a deposited token. Token ids are only unique inside a collection.
+ * Also used as a pagination cursor when querying deposited tokens
+ * */
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokenKey {
+    pub collection: String,
+    pub token_id: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct ContractInfoResponse {
     pub name: String,
-    pub nft_address: String,
     pub owner: String,
+    pub relayer: Option<String>,
+    pub withdraw_window: Option<u64>,
+    pub lock_policy: LockPolicy,
+    pub destination_prefix: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CollectionsResponse {
+    pub collections: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ReceiveMsg {
-    DepositNft { token_id: String },
+    DepositNft {
+        token_id: String,
+        destination_address: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -122,3 +229,31 @@ pub struct TokensResponse {
 pub struct TokenInfoResponse {
     pub tokens: Vec<TokenInfo>,
 }
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct EventsResponse {
+    pub events: Vec<Event>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DepositSnapshotResponse {
+    pub collection: String,
+    pub token_id: String,
+    pub token_uri: Option<String>,
+    pub extension_hash: Binary,
+    pub snapshot_time: Timestamp,
+}
+
+pub fn to_snapshot_response(
+    collection: String,
+    token_id: String,
+    snapshot: DepositSnapshot,
+) -> DepositSnapshotResponse {
+    DepositSnapshotResponse {
+        collection,
+        token_id,
+        token_uri: snapshot.token_uri,
+        extension_hash: snapshot.extension_hash,
+        snapshot_time: snapshot.snapshot_time,
+    }
+}
@@ -1,32 +1,139 @@
-use cosmwasm_std::{Addr, Timestamp};
-use cw_storage_plus::{Index, IndexList, MultiIndex};
+use cosmwasm_std::{Addr, Binary, Timestamp};
+use cw_storage_plus::{Index, IndexList, MultiIndex, U8Key};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct ContractInfo {
+    pub name: String,
+    pub owner: Addr,
+    pub relayer: Option<Addr>,
+    pub withdraw_window: Option<u64>,
+    pub lock_policy: LockPolicy,
+    pub destination_prefix: String,
+}
+
+/**
+ * Contract information of the escrows deployed before the contracts were versioned.
+ * They only accepted a single collection (nft_address)
+ * */
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct LegacyContractInfo {
     pub name: String,
     pub nft_address: Addr,
     pub owner: Addr,
 }
 
+/**
+ * Decides whether deposited tokens can be withdrawn by their depositor
+ * Whatever the policy, a token marked as migrated can't be withdrawn (it would be spent on both chains)
+ * From the loosest to the tightest policy :
+ *  Withdrawable: tokens can always be withdrawn until they are migrated
+ *  WithdrawableUntilMigrated: tokens can be withdrawn until they are marked as migrated.
+ *      Equivalent to Withdrawable (the owner can switch between them), kept for API compatibility
+ *  WithdrawableUntil: tokens can be withdrawn until they are marked as migrated and until the deadline
+ *  LockedForever: tokens can never be withdrawn
+ * */
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LockPolicy {
+    LockedForever,
+    Withdrawable,
+    WithdrawableUntil { deadline: Timestamp },
+    WithdrawableUntilMigrated,
+}
+
+impl fmt::Display for LockPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockPolicy::LockedForever => write!(f, "locked_forever"),
+            LockPolicy::Withdrawable => write!(f, "withdrawable"),
+            LockPolicy::WithdrawableUntil { deadline } => {
+                write!(f, "withdrawable_until:{}", deadline)
+            }
+            LockPolicy::WithdrawableUntilMigrated => write!(f, "withdrawable_until_migrated"),
+        }
+    }
+}
+
+impl LockPolicy {
+    fn strictness(&self) -> u8 {
+        match self {
+            // Migrated tokens can't be withdrawn under any policy, so these two are equivalent
+            LockPolicy::Withdrawable | LockPolicy::WithdrawableUntilMigrated => 0,
+            LockPolicy::WithdrawableUntil { .. } => 1,
+            LockPolicy::LockedForever => 2,
+        }
+    }
+
+    /// Returns true if every withdrawal refused by `self` is also refused by `new_policy`
+    pub fn can_be_tightened_to(&self, new_policy: &LockPolicy) -> bool {
+        match (self, new_policy) {
+            (
+                LockPolicy::WithdrawableUntil { deadline },
+                LockPolicy::WithdrawableUntil {
+                    deadline: new_deadline,
+                },
+            ) => new_deadline <= deadline,
+            _ => new_policy.strictness() >= self.strictness(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct TokenOwner {
     pub owner: Addr,
     pub migrated: bool,
     pub deposit_time: Timestamp,
     pub migrate_time: Timestamp,
+    pub destination_tx: Option<String>,
+    pub destination_address: Option<String>,
+}
+
+/**
+ * Metadata of a token, as queried from its collection when it was deposited
+ * extension_hash is the sha256 hash of the canonical json of the extension returned by the collection (the same hash as the minter)
+ * */
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DepositSnapshot {
+    pub token_uri: Option<String>,
+    pub extension_hash: Binary,
+    pub snapshot_time: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Deposit,
+    Withdraw,
+    MarkMigrated,
+}
+
+/**
+ * Entry of the escrow event log. Events are numbered in the order they happened, starting at 1
+ * */
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Event {
+    pub seq: u64,
+    pub kind: EventKind,
+    pub collection: Addr,
+    pub token_id: String,
+    pub depositor: Addr,
+    pub time: Timestamp,
 }
 
 pub struct TokenIndexes<'a> {
     pub owner: MultiIndex<'a, (Addr, Vec<u8>), TokenOwner>,
-    pub migrated: MultiIndex<'a, (bool, Vec<u8>), TokenOwner>,
+    pub migrated: MultiIndex<'a, (U8Key, Vec<u8>), TokenOwner>,
+    pub owner_migrated: MultiIndex<'a, (Addr, U8Key, Vec<u8>), TokenOwner>,
 }
 
 impl<'a> IndexList<TokenOwner> for TokenIndexes<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<TokenOwner>> + '_> {
-        let v: Vec<&dyn Index<TokenOwner>> = vec![&self.owner];
+        let v: Vec<&dyn Index<TokenOwner>> =
+            vec![&self.owner, &self.migrated, &self.owner_migrated];
         Box::new(v.into_iter())
     }
 }
@@ -1,21 +1,38 @@
 use cosmwasm_std::{
-    coins, entry_point, to_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdError, StdResult, Uint128,
+    coins, from_binary, to_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, Response, StdError, StdResult,
 };
+use cw20::{Balance, Cw20Coin, Cw20CoinVerified, Cw20ReceiveMsg};
+use cw_storage_plus::Bound;
+use cw_utils::NativeBalance;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 #[cfg(not(feature = "library"))]
 use std::fmt::Debug;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, FeeResponse, InstantiateMsg, MigrateMsg, MintRequest, QueryMsg};
-use crate::state::ContractInfo;
+use crate::fee::{check_fee_split, check_price_schedule, price_tiers, split_fee, TOTAL_BPS};
+use crate::merkle::{merkle_leaf, verify_proof};
+use crate::msg::{
+    ContractInfoResponse, ExecuteMsg, Expiration, FeeResponse, InstantiateMsg, MerkleRootResponse,
+    MigrateMsg, MintCheck, MintRequest, MintSignature, MinterKeysResponse, QueryMsg, ReceiveMsg,
+    VerifyMintRequestResponse,
+};
+use crate::signing::{adr036_sign_doc, signing_bytes};
+use crate::state::{ContractInfo, FeeShare, FeeSplit, KeyScheme, MinterKey, PriceTier};
 use anyhow::{anyhow, Result};
 
-use cw721_base::ExecuteMsg as Cw721ExecuteMsg;
+use cw20_base::msg::ExecuteMsg as Cw20ExecuteMsg;
+use cw721_base::{ExecuteMsg as Cw721ExecuteMsg, MintMsg};
 
 use crate::msg::into_cosmos_msg;
-use crate::state::{is_owner, CONTRACT_INFO};
+use crate::state::{
+    is_owner, LegacyContractInfo, CLAIMED_LEAVES, CONTRACT_INFO, DEFAULT_KEY_ID,
+    LEGACY_CONTRACT_INFO, MERKLE_ROOT, MINTER_KEYS, USED_REQUESTS,
+};
+use cw2::{get_contract_version, set_contract_version};
+use migration_utils::version::parse_version;
 
 /**
  * This package is used to mint NFTs on CW721 standard NFTs (see https://github.com/CosmWasm/cw-nfts/)
@@ -24,11 +41,12 @@ use crate::state::{is_owner, CONTRACT_INFO};
  * This is used to migrate NFTs from Terra 1 to Terra 2.0.
  * args:
  *  owner: only address that can change the contract parameters. Default : initialization sender
- *  minter: public key of the address that signs the mint authorization message off-chain.
- *  treasury : treasury that will receive the mint fee
- *  project_treasury : treasury that will receive the project mint fee
- *  fee_price : fee amount in uluna that will be transfered to the treasury
- *  project_price: fee amount in uluna that will be transfered to the project_treasury
+ *  minter: public key of the address that signs the mint authorization message off-chain. It is the default key of the keyring
+ *  minter_scheme: signature scheme of the minter key (secp256k1 or ed25519). Default : secp256k1
+ *  threshold: number of distinct keys that have to sign a mint request. Default : 1
+ *  prices : total mint price in each of the denoms accepted to pay the mint fee
+ *  fee_shares : recipients of the mint fee, with their share in basis points (the shares sum to 10000)
+ *  dust_recipient : receives the rounding dust of the split. Default : initialization sender
  * */
 pub fn instantiate(
     deps: DepsMut,
@@ -36,8 +54,15 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response> {
-    // Verify the contract name
+    // Verify the contract name, threshold and fee split
     msg.validate()?;
+    let fee_split = validate_fee_split(
+        deps.as_ref(),
+        &msg.prices,
+        msg.fee_shares,
+        msg.dust_recipient
+            .unwrap_or_else(|| info.sender.to_string()),
+    )?;
     // store token info
     let data = ContractInfo {
         name: msg.name,
@@ -46,22 +71,22 @@ pub fn instantiate(
             .map(|x| deps.api.addr_validate(&x))
             .unwrap_or_else(|| Ok(info.sender.clone()))?,
         nft_contract: None,
-        minter: msg.minter,
-        treasury: msg
-            .treasury
-            .map(|x| deps.api.addr_validate(&x))
-            .unwrap_or(Ok(info.sender))?,
-        fee_price: msg.fee_price,
-        project_treasury: deps.api.addr_validate(&msg.project_treasury)?,
-        project_price: msg.project_price,
+        threshold: msg.threshold.unwrap_or(1),
+        prices: msg.prices,
+        cw20_prices: vec![],
+        price_schedule: vec![],
+        fee_split,
     };
     CONTRACT_INFO.save(deps.storage, &data)?;
+    let minter_scheme = msg.minter_scheme.unwrap_or_default();
+    validate_pubkey(&msg.minter, minter_scheme)?;
+    save_default_key(deps, msg.minter, minter_scheme)?;
     // Initialisation with fixed rates
 
     Ok(Response::default().add_attribute("fee_contract", "init"))
 }
 
-pub fn execute<T: Clone + Serialize + Debug>(
+pub fn execute<T: Clone + Serialize + DeserializeOwned + Debug>(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
@@ -70,147 +95,739 @@ pub fn execute<T: Clone + Serialize + Debug>(
     match msg {
         ExecuteMsg::Mint {
             mint_request,
-            signature,
-        } => mint(deps, env, info, mint_request, signature),
-        ExecuteMsg::SetMinter { minter } => set_minter(deps, env, info, minter),
-        ExecuteMsg::SetOwner { owner } => set_owner(deps, env, info, owner),
-        ExecuteMsg::SetFeePrice { price } => set_fee_price(deps, env, info, price),
-        ExecuteMsg::SetProjectFeePrice { price } => set_project_price(deps, env, info, price),
-        ExecuteMsg::SetTreasury { treasury } => set_treasury(deps, env, info, treasury),
-        ExecuteMsg::SetProjectTreasury { treasury } => {
-            set_project_treasury(deps, env, info, treasury)
+            signatures,
+        } => mint(
+            deps,
+            env,
+            info.sender.to_string(),
+            info.funds.into(),
+            mint_request,
+            signatures,
+        ),
+        ExecuteMsg::Receive(msg) => receive::<T>(deps, env, info, msg),
+        ExecuteMsg::MintWithProof { mint_msg, proof } => {
+            mint_with_proof(deps, env, info, mint_msg, proof)
         }
+        ExecuteMsg::SetMerkleRoot { merkle_root } => set_merkle_root(deps, env, info, merkle_root),
+        ExecuteMsg::SetMinter { minter, scheme } => set_minter(deps, env, info, minter, scheme),
+        ExecuteMsg::AddMinterKey {
+            key_id,
+            pubkey,
+            scheme,
+            valid_from,
+            valid_until,
+        } => add_minter_key(
+            deps,
+            env,
+            info,
+            MinterKey {
+                key_id,
+                pubkey,
+                scheme: scheme.unwrap_or_default(),
+                valid_from,
+                valid_until,
+            },
+        ),
+        ExecuteMsg::RevokeMinterKey { key_id } => revoke_minter_key(deps, env, info, key_id),
+        ExecuteMsg::SetThreshold { threshold } => set_threshold(deps, env, info, threshold),
+        ExecuteMsg::SetOwner { owner } => set_owner(deps, env, info, owner),
+        ExecuteMsg::SetFeeSplit {
+            prices,
+            shares,
+            dust_recipient,
+        } => set_fee_split(deps, env, info, prices, shares, dust_recipient),
+        ExecuteMsg::SetCw20Prices { prices } => set_cw20_prices(deps, env, info, prices),
+        ExecuteMsg::SetPriceSchedule { tiers } => set_price_schedule(deps, env, info, tiers),
         ExecuteMsg::SetNftContract { nft_contract } => {
             set_nft_contract(deps, env, info, nft_contract)
         }
     }
 }
 
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary> {
+pub fn query<T: Serialize>(deps: Deps, env: Env, msg: QueryMsg<T>) -> Result<Binary> {
     match msg {
+        QueryMsg::ContractInfo {} => to_binary(&contract_info(deps)?).map_err(|x| anyhow!(x)),
         QueryMsg::FeePrice {} => {
             let contract_info = CONTRACT_INFO.load(deps.storage)?;
+            let (current_tier, next_tier) = price_tiers(&contract_info, env.block.time);
             let fee_response = FeeResponse {
-                fee_price: contract_info.fee_price,
-                project_price: contract_info.project_price,
+                current_tier,
+                next_tier,
+                fee_split: contract_info.fee_split,
             };
 
             to_binary(&fee_response).map_err(|x| anyhow!(x))
         }
+        QueryMsg::MinterKeys { start_after, limit } => {
+            to_binary(&minter_keys(deps, start_after, limit)?).map_err(|x| anyhow!(x))
+        }
+        QueryMsg::MerkleRoot {} => to_binary(&MerkleRootResponse {
+            merkle_root: MERKLE_ROOT.may_load(deps.storage)?,
+        })
+        .map_err(|x| anyhow!(x)),
+        QueryMsg::VerifyMintRequest {
+            mint_request,
+            signatures,
+            funds,
+            cw20_funds,
+        } => to_binary(&query_verify_mint_request(
+            deps,
+            env,
+            mint_request,
+            signatures,
+            funds,
+            cw20_funds,
+        )?)
+        .map_err(|x| anyhow!(x)),
     }
 }
 
-/** The contract is migratable. No conditions there.
+/** The contract is migratable.
  * Allows to update the minting conditions if necessary
+ * The contract has to be the same minter (contract_name) and the new code can't be older than the stored one.
+ * Minters deployed before the contracts were versioned get their contract information moved to its new storage key,
+ * and their minter public key registered as the default key of the keyring
+ * args:
+ *  contract_name, contract_version: cw2 information of the contract wrapping this package
+ * */
+pub fn migrate(
+    mut deps: DepsMut,
+    _env: Env,
+    _msg: MigrateMsg,
+    contract_name: &str,
+    contract_version: &str,
+) -> Result<Response, ContractError> {
+    let from_version = if CONTRACT_INFO.may_load(deps.storage)?.is_none() {
+        let legacy = LEGACY_CONTRACT_INFO.load(deps.storage)?;
+        let fee_split = legacy_fee_split(&legacy);
+        CONTRACT_INFO.save(
+            deps.storage,
+            &ContractInfo {
+                name: legacy.name,
+                owner: legacy.owner,
+                nft_contract: legacy.nft_contract,
+                threshold: 1,
+                // Legacy minters were paid in uluna only
+                prices: coins((legacy.fee_price + legacy.project_price).u128(), "uluna"),
+                cw20_prices: vec![],
+                price_schedule: vec![],
+                fee_split,
+            },
+        )?;
+        save_default_key(deps.branch(), legacy.minter, KeyScheme::Secp256k1)?;
+        "legacy".to_string()
+    } else {
+        let stored = get_contract_version(deps.storage)?;
+        if stored.contract != contract_name {
+            return Err(ContractError::WrongContract {
+                expected: contract_name.to_string(),
+                found: stored.contract,
+            });
+        }
+        if parse_version(&stored.version).map_err(StdError::generic_err)?
+            > parse_version(contract_version).map_err(StdError::generic_err)?
+        {
+            return Err(ContractError::CannotDowngrade {
+                stored: stored.version,
+                current: contract_version.to_string(),
+            });
+        }
+        stored.version
+    };
+    set_contract_version(deps.storage, contract_name, contract_version)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from_version)
+        .add_attribute("to_version", contract_version))
+}
+
+/**
+ * Converts the treasury and project prices of a legacy minter to shares of the total price
+ * The rounding dust goes to the treasury
  * */
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    // No state migrations performed, just returned a Response
-    Ok(Response::default())
+fn legacy_fee_split(legacy: &LegacyContractInfo) -> FeeSplit {
+    let total_price = legacy.fee_price + legacy.project_price;
+    let treasury_bps = if total_price.is_zero() {
+        TOTAL_BPS
+    } else {
+        legacy
+            .fee_price
+            .multiply_ratio(TOTAL_BPS, total_price)
+            .u128() as u32
+    };
+    FeeSplit {
+        shares: vec![
+            FeeShare {
+                recipient: legacy.treasury.to_string(),
+                bps: treasury_bps as u16,
+            },
+            FeeShare {
+                recipient: legacy.project_treasury.to_string(),
+                bps: (TOTAL_BPS - treasury_bps) as u16,
+            },
+        ]
+        .into_iter()
+        .filter(|share| share.bps != 0)
+        .collect(),
+        dust_recipient: legacy.treasury.to_string(),
+    }
 }
 
 /** This function allows minting NFTs/
- *  It verifies the signature sent corresponds to the minter address.
+ *  It verifies the request was signed by at least `threshold` distinct active keys of the minter keyring.
+ *  A mint request can only be used once and before it expires.
  *  It then sends the message to the NFT contract
  * args:
+ *  sender: address the surplus of the funds is refunded to
+ *  funds: funds sent to pay the mint, native coins or cw20 tokens
  *  mint_request: information to mint the NFT (token_id, optional extension...)
- *  signature : signature of the mint_request message by the authority
+ *  signatures : signatures of the mint_request message by the authorities, with the key that signed them.
+ *      If the key is not specified, the contract looks for it
  *  T: extension type of the NFT minted
  * */
 pub fn mint<T: Serialize + Clone + Debug>(
     deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
+    env: Env,
+    sender: String,
+    funds: Balance,
     mint_request: MintRequest<T>,
-    signature: String,
+    signatures: Vec<MintSignature>,
 ) -> Result<Response> {
     let contract_info = CONTRACT_INFO.load(deps.storage)?;
 
-    // We verify the contract has been initialized
-    if contract_info.nft_contract.is_none() {
-        return Err(anyhow!(ContractError::ContractNotInitialized {}));
-    }
-
-    // The fee can be paid in uluna only.
-    // The price is fixed
-    let fee_price = contract_info.fee_price;
-    let project_price = contract_info.project_price;
-    let total_price = fee_price + project_price;
-    if total_price != Uint128::zero() {
-        if info.funds.len() != 1 {
-            return Err(anyhow!(ContractError::FeeNotPaid {}));
-        }
-
-        let funds = info.funds[0].clone();
-        if funds.denom != "uluna" || funds.amount != total_price {
-            return Err(anyhow!(ContractError::FeeNotPaidCorrectly {
-                required: total_price.u128(),
-                provided: funds.amount.u128()
-            },));
-        }
-    }
-
-    // Now we verify the message was indeed signed by the trusted minter
-    validate_request_signature(
-        &deps.as_ref(),
-        &contract_info.minter,
+    let verified = verify_mint_request(
+        deps.as_ref(),
+        &env,
+        &funds,
+        &contract_info,
         &mint_request,
-        &signature,
-    )?;
+        signatures,
+    )
+    .map_err(|(_, err)| err)?;
+    USED_REQUESTS.save(deps.storage, &verified.request_hash, &Empty {})?;
 
     // Once the signature is validated, we can send a mint message to the nft contract
     let mint_message = into_cosmos_msg(
         Cw721ExecuteMsg::Mint(mint_request.mint_msg),
-        contract_info.nft_contract.unwrap(),
+        verified.nft_contract,
         None,
     )?;
 
     let response = Response::new()
         .add_attribute("action", "migrated_token")
+        .add_attributes(
+            verified
+                .signers
+                .into_iter()
+                .map(|key| ("key_id", key.key_id)),
+        )
         .add_message(mint_message);
 
     // And add the funds transfer to the treasury and the project
-    let response = if fee_price != Uint128::zero() {
-        response.add_message(BankMsg::Send {
-            amount: coins(fee_price.u128(), "uluna"),
-            to_address: contract_info.treasury.to_string(),
-        })
-    } else {
-        response
+    add_fee_messages(response, &contract_info, &sender, verified.payment)
+}
+
+/** This function receives the cw20 tokens sent to pay a mint
+ *  The tokens are sent with a ReceiveMsg, that holds the mint request and its signatures (see mint)
+ * */
+pub fn receive<T: Serialize + DeserializeOwned + Clone + Debug>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response> {
+    // The fee is paid in cw20 tokens only, native coins would neither be used nor refunded
+    if let Some(coin) = info.funds.first() {
+        return Err(anyhow!(ContractError::FeeDenomNotAccepted {
+            denom: coin.denom.clone()
+        }));
+    }
+    // The sender of the message is the cw20 contract, the tokens were sent by wrapper.sender
+    let funds = Balance::Cw20(Cw20CoinVerified {
+        address: info.sender,
+        amount: wrapper.amount,
+    });
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::Mint {
+            mint_request,
+            signatures,
+        } => mint::<T>(deps, env, wrapper.sender, funds, mint_request, signatures),
+    }
+}
+
+/**
+ * Mint request that passed all the checks of verify_mint_request
+ * */
+struct VerifiedMintRequest {
+    nft_contract: String,
+    request_hash: Vec<u8>,
+    signers: Vec<MinterKey>,
+    /// Fee paid with the request
+    payment: Payment,
+}
+
+/** Util that runs all the checks needed before minting a signed request, without modifying the state
+ * The checks are run in this order : the contract is initialized, the request targets its nft contract, the fee is paid,
+ * the request is not expired nor already used, the signatures are valid and there are enough of them.
+ * Returns the first check that failed with its error
+ * */
+fn verify_mint_request<T: Serialize>(
+    deps: Deps,
+    env: &Env,
+    funds: &Balance,
+    contract_info: &ContractInfo,
+    mint_request: &MintRequest<T>,
+    signatures: Vec<MintSignature>,
+) -> Result<VerifiedMintRequest, (MintCheck, anyhow::Error)> {
+    // We verify the contract has been initialized
+    let nft_contract = contract_info.nft_contract.clone().ok_or((
+        MintCheck::ContractInitialized,
+        anyhow!(ContractError::ContractNotInitialized {}),
+    ))?;
+
+    // The request has to be made for the collection of this minter
+    if mint_request.nft_contract != nft_contract {
+        return Err((
+            MintCheck::NftContract,
+            anyhow!(ContractError::NftContractMismatch {
+                expected: nft_contract,
+                provided: mint_request.nft_contract.clone(),
+            }),
+        ));
+    }
+
+    // The price depends on the tier of the price schedule in effect
+    let (tier, _) = price_tiers(contract_info, env.block.time);
+    let payment = check_fee(&tier, funds).map_err(|err| (MintCheck::Fee, err))?;
+
+    // The authorization can't be used once expired, it has to expire at some point
+    if matches!(mint_request.expires_at, Expiration::Never {}) {
+        return Err((
+            MintCheck::Expiration,
+            anyhow!(ContractError::MintRequestNeverExpires {}),
+        ));
+    }
+    if mint_request.expires_at.is_expired(&env.block) {
+        return Err((
+            MintCheck::Expiration,
+            anyhow!(ContractError::MintRequestExpired {}),
+        ));
+    }
+
+    // Nor be used twice (even if the token was burned in the meantime)
+    let request_hash =
+        request_hash(env, mint_request).map_err(|err| (MintCheck::Signature, err))?;
+    if USED_REQUESTS.has(deps.storage, &request_hash) {
+        return Err((
+            MintCheck::AlreadyUsed,
+            anyhow!(ContractError::MintRequestAlreadyUsed {}),
+        ));
+    }
+
+    // Now we verify the message was indeed signed by enough trusted minter keys
+    let signers = find_signing_keys(deps, env, mint_request, signatures, &request_hash)
+        .map_err(|err| (MintCheck::Signature, err))?;
+    if (signers.len() as u32) < contract_info.threshold {
+        return Err((
+            MintCheck::Threshold,
+            anyhow!(ContractError::NotEnoughSignatures {
+                threshold: contract_info.threshold,
+                provided: signers.len() as u32,
+            }),
+        ));
+    }
+
+    Ok(VerifiedMintRequest {
+        nft_contract,
+        request_hash,
+        signers,
+        payment,
+    })
+}
+
+/**
+ * Runs the checks of a mint without executing it, so that a signature can be tested without paying gas
+ * args:
+ *  funds: funds that would be sent with the Mint message
+ *  cw20_funds: cw20 tokens that would be sent with the Mint message instead (see receive)
+ * */
+pub fn query_verify_mint_request<T: Serialize>(
+    deps: Deps,
+    env: Env,
+    mint_request: MintRequest<T>,
+    signatures: Vec<MintSignature>,
+    funds: Vec<Coin>,
+    cw20_funds: Option<Cw20Coin>,
+) -> StdResult<VerifyMintRequestResponse> {
+    let contract_info = CONTRACT_INFO.load(deps.storage)?;
+
+    // Cw20 tokens are sent in a cw20 Send message, without native funds (see receive)
+    let funds = match cw20_funds {
+        Some(cw20_funds) if funds.is_empty() => Balance::Cw20(Cw20CoinVerified {
+            address: deps.api.addr_validate(&cw20_funds.address)?,
+            amount: cw20_funds.amount,
+        }),
+        Some(_) => {
+            return Err(StdError::generic_err(
+                "Native funds and cw20 tokens can't be sent together",
+            ))
+        }
+        None => funds.into(),
     };
-    let response = if project_price != Uint128::zero() {
-        response.add_message(BankMsg::Send {
-            amount: coins(project_price.u128(), "uluna"),
-            to_address: contract_info.project_treasury.to_string(),
+
+    Ok(
+        match verify_mint_request(
+            deps,
+            &env,
+            &funds,
+            &contract_info,
+            &mint_request,
+            signatures,
+        ) {
+            Ok(verified) => VerifyMintRequestResponse {
+                valid: true,
+                failed_check: None,
+                error: None,
+                key_ids: verified.signers.into_iter().map(|key| key.key_id).collect(),
+            },
+            Err((check, err)) => VerifyMintRequestResponse {
+                valid: false,
+                failed_check: Some(check),
+                error: Some(err.to_string()),
+                key_ids: vec![],
+            },
+        },
+    )
+}
+
+/** This function allows minting NFTs without a signature, from the Merkle root published by the owner
+ *  It verifies the mint message is a leaf of the tree (see merkle_leaf) and that this leaf was not claimed yet.
+ *  It then sends the message to the NFT contract. The fee is the same as for signed mint requests
+ * args:
+ *  mint_msg: information to mint the NFT (token_id, owner, token_uri, extension)
+ *  proof : hashes of the siblings of the leaf, from the leaf to the root
+ *  T: extension type of the NFT minted
+ * */
+pub fn mint_with_proof<T: Serialize + Clone + Debug>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mint_msg: MintMsg<T>,
+    proof: Vec<Binary>,
+) -> Result<Response> {
+    let contract_info = CONTRACT_INFO.load(deps.storage)?;
+
+    // We verify the contract has been initialized
+    let nft_contract = contract_info
+        .nft_contract
+        .clone()
+        .ok_or(ContractError::ContractNotInitialized {})?;
+    let merkle_root = MERKLE_ROOT
+        .may_load(deps.storage)?
+        .ok_or(ContractError::MerkleRootNotSet {})?;
+
+    let (tier, _) = price_tiers(&contract_info, env.block.time);
+    let payment = check_fee(&tier, &info.funds.into())?;
+
+    // The leaf has to be in the published tree, and can only be claimed once
+    let leaf = merkle_leaf(&mint_msg)?;
+    if !verify_proof(&merkle_root, &leaf, &proof) {
+        return Err(anyhow!(ContractError::InvalidProof {}));
+    }
+    if CLAIMED_LEAVES.has(deps.storage, &leaf) {
+        return Err(anyhow!(ContractError::LeafAlreadyClaimed {}));
+    }
+    CLAIMED_LEAVES.save(deps.storage, &leaf, &Empty {})?;
+
+    let mint_message = into_cosmos_msg(Cw721ExecuteMsg::Mint(mint_msg), nft_contract, None)?;
+
+    let response = Response::new()
+        .add_attribute("action", "migrated_token")
+        .add_attribute("leaf", Binary::from(leaf).to_base64())
+        .add_message(mint_message);
+
+    add_fee_messages(response, &contract_info, info.sender.as_str(), payment)
+}
+
+/**
+ * Funds sent with a mint, split between the paid fee and the surplus refunded to the sender
+ * */
+struct Payment {
+    fee: Option<Balance>,
+    refund: Balance,
+}
+
+/** Util to verify the mint fee was sent with the message
+ * The fee is paid with a coin or with a cw20 token accepted in the current price tier, that covers at least the price in this denom or token.
+ * The mint is free when no denom nor token is accepted or when a price is zero
+ * Returns the paid fee and the surplus (including the coins that don't pay the fee), that is refunded to the sender
+ * */
+fn check_fee(tier: &PriceTier, funds: &Balance) -> Result<Payment> {
+    let free = (tier.prices.is_empty() && tier.cw20_prices.is_empty())
+        || tier.prices.iter().any(|price| price.amount.is_zero())
+        || tier.cw20_prices.iter().any(|price| price.amount.is_zero());
+    let funds = match funds {
+        Balance::Native(NativeBalance(funds)) => funds,
+        Balance::Cw20(funds) => {
+            let price = tier.cw20_price(funds.address.as_str()).ok_or(
+                ContractError::FeeDenomNotAccepted {
+                    denom: funds.address.to_string(),
+                },
+            )?;
+            if funds.amount < price.amount {
+                return Err(anyhow!(ContractError::FeeNotPaidCorrectly {
+                    required: format!("{}{}", price.amount, price.address),
+                    provided: format!("{}{}", funds.amount, funds.address),
+                }));
+            }
+            return Ok(Payment {
+                fee: Some(Balance::Cw20(Cw20CoinVerified {
+                    address: funds.address.clone(),
+                    amount: price.amount,
+                })),
+                refund: Balance::Cw20(Cw20CoinVerified {
+                    address: funds.address.clone(),
+                    amount: funds.amount - price.amount,
+                }),
+            });
+        }
+    };
+    if free {
+        return Ok(Payment {
+            fee: None,
+            refund: funds.clone().into(),
+        });
+    }
+
+    // The fee is paid with the first coin that covers its price
+    let price = funds
+        .iter()
+        .find_map(|coin| {
+            tier.price(&coin.denom)
+                .filter(|price| coin.amount >= price.amount)
         })
-    } else {
-        response
+        .ok_or_else(|| {
+            match funds
+                .iter()
+                .find_map(|coin| tier.price(&coin.denom).map(|price| (coin, price)))
+            {
+                Some((coin, price)) => ContractError::FeeNotPaidCorrectly {
+                    required: price.to_string(),
+                    provided: coin.to_string(),
+                },
+                None => match funds.first() {
+                    Some(coin) => ContractError::FeeDenomNotAccepted {
+                        denom: coin.denom.clone(),
+                    },
+                    None => ContractError::FeeNotPaid {},
+                },
+            }
+        })?;
+    let refund = funds
+        .iter()
+        .map(|coin| {
+            if coin.denom == price.denom {
+                Coin {
+                    denom: coin.denom.clone(),
+                    amount: coin.amount - price.amount,
+                }
+            } else {
+                coin.clone()
+            }
+        })
+        .filter(|coin| !coin.amount.is_zero())
+        .collect::<Vec<Coin>>();
+    Ok(Payment {
+        fee: Some(vec![price.clone()].into()),
+        refund: refund.into(),
+    })
+}
+
+/** Util to add the transfer of the paid fee to its recipients to a mint response
+ * The fee is split according to the shares of the contract (see split_fee), in the denom or token it was paid in.
+ * Cw20 tokens are forwarded with cw20 Transfer messages.
+ * The surplus is refunded to the sender, the refunded amounts are reported in the refund attributes
+ * */
+fn add_fee_messages(
+    response: Response,
+    contract_info: &ContractInfo,
+    sender: &str,
+    payment: Payment,
+) -> Result<Response> {
+    let mut messages: Vec<CosmosMsg> = match payment.fee {
+        None => vec![],
+        Some(Balance::Native(NativeBalance(fee))) => fee
+            .iter()
+            .flat_map(|fee| {
+                split_fee(&contract_info.fee_split, fee.amount)
+                    .into_iter()
+                    .map(move |(recipient, amount)| {
+                        BankMsg::Send {
+                            to_address: recipient,
+                            amount: coins(amount.u128(), &fee.denom),
+                        }
+                        .into()
+                    })
+            })
+            .collect(),
+        Some(Balance::Cw20(fee)) => split_fee(&contract_info.fee_split, fee.amount)
+            .into_iter()
+            .map(|(recipient, amount)| {
+                into_cosmos_msg(
+                    Cw20ExecuteMsg::Transfer { recipient, amount },
+                    fee.address.clone(),
+                    None,
+                )
+            })
+            .collect::<StdResult<Vec<CosmosMsg>>>()?,
     };
 
-    Ok(response)
+    let mut refunds: Vec<String> = vec![];
+    match payment.refund {
+        Balance::Native(NativeBalance(refund)) if !refund.is_empty() => {
+            refunds.extend(refund.iter().map(|coin| coin.to_string()));
+            messages.push(
+                BankMsg::Send {
+                    to_address: sender.to_string(),
+                    amount: refund,
+                }
+                .into(),
+            );
+        }
+        Balance::Cw20(refund) if !refund.amount.is_zero() => {
+            refunds.push(format!("{}{}", refund.amount, refund.address));
+            messages.push(into_cosmos_msg(
+                Cw20ExecuteMsg::Transfer {
+                    recipient: sender.to_string(),
+                    amount: refund.amount,
+                },
+                refund.address,
+                None,
+            )?);
+        }
+        _ => {}
+    }
+
+    Ok(response
+        .add_messages(messages)
+        .add_attributes(refunds.into_iter().map(|refund| ("refund", refund))))
+}
+
+/** Util to compute the hash of a mint request, that is signed by the minter authority
+ * The request is bound to the current chain and to this minter contract (see signing_bytes)
+ * It also identifies the request once it has been used
+ * */
+pub fn request_hash<T: Serialize>(env: &Env, request: &MintRequest<T>) -> Result<Vec<u8>> {
+    let data_bytes = signing_bytes(&env.block.chain_id, env.contract.address.as_str(), request)?;
+    Ok(Sha256::digest(data_bytes.as_slice()).to_vec())
+}
+
+/** Util to find the keys of the keyring that signed a mint request
+ * Every signature has to be valid. A key (public key) signing multiple times is only counted once
+ * ADR-036 signatures are verified against the sign doc wrapping the signing_bytes of the request
+ * Returns the distinct signing keys
+ * */
+fn find_signing_keys<T: Serialize>(
+    deps: Deps,
+    env: &Env,
+    mint_request: &MintRequest<T>,
+    signatures: Vec<MintSignature>,
+    request_hash: &[u8],
+) -> Result<Vec<MinterKey>> {
+    let mut signers: Vec<MinterKey> = vec![];
+    for signature in signatures {
+        let message_hash = match signature.adr036_signer {
+            Some(signer) => {
+                let data = signing_bytes(
+                    &env.block.chain_id,
+                    env.contract.address.as_str(),
+                    mint_request,
+                )?;
+                Sha256::digest(&adr036_sign_doc(&signer, &data)?).to_vec()
+            }
+            None => request_hash.to_vec(),
+        };
+        let key = find_signing_key(
+            deps,
+            env,
+            signature.key_id,
+            &message_hash,
+            &signature.signature,
+        )?;
+        if !signers.iter().any(|signer| signer.pubkey == key.pubkey) {
+            signers.push(key);
+        }
+    }
+    Ok(signers)
+}
+
+/** Util to find the key of the keyring that signed a mint request
+ * The key has to be active at the current block time
+ * args:
+ *  key_id: key announced by the sender. If None, all the active keys are tried
+ * Returns the signing key
+ * */
+fn find_signing_key(
+    deps: Deps,
+    env: &Env,
+    key_id: Option<String>,
+    request_hash: &[u8],
+    signature: &String,
+) -> Result<MinterKey> {
+    match key_id {
+        Some(key_id) => {
+            let key = MINTER_KEYS.may_load(deps.storage, &key_id)?.ok_or(
+                ContractError::MinterKeyNotFound {
+                    key_id: key_id.clone(),
+                },
+            )?;
+            if !key.is_active(&env.block) {
+                return Err(anyhow!(ContractError::MinterKeyInactive { key_id }));
+            }
+            validate_request_signature(&deps, &key, request_hash, signature)?;
+            Ok(key)
+        }
+        None => {
+            for key in MINTER_KEYS.range(deps.storage, None, None, Order::Ascending) {
+                let (_, key) = key?;
+                if key.is_active(&env.block)
+                    && validate_request_signature(&deps, &key, request_hash, signature).is_ok()
+                {
+                    return Ok(key);
+                }
+            }
+            Err(anyhow!(ContractError::Unauthorized {}))
+        }
+    }
 }
 
 /** Util to validate that the signature has been correctly signed by the minter authority
+ * The verification depends on the signature scheme of the key
  * args:
- *  request: message that was signed by the authority
- *  base64_pub_key: public key of the signing authority
+ *  request_hash: hash of the message that was signed by the authority
+ *  key: key of the signing authority (base64 public key and scheme)
  *  base64_sig : signature of the request by the authority
  * */
-fn validate_request_signature<T: Serialize>(
+fn validate_request_signature(
     deps: &Deps,
-    base64_pub_key: &String,
-    request: &MintRequest<T>,
+    key: &MinterKey,
+    request_hash: &[u8],
     base64_sig: &String,
 ) -> Result<()> {
-    let pub_key = base64::decode(base64_pub_key)?;
+    let pub_key = base64::decode(&key.pubkey)?;
     let signature = base64::decode(base64_sig)?;
 
-    let data_bytes = to_binary(request)?.to_vec();
-    let data_hash = Sha256::digest(data_bytes.as_slice());
-
-    let verification_result =
-        deps.api
-            .secp256k1_verify(&data_hash, &signature, pub_key.as_ref())?;
+    let verification_result = match key.scheme {
+        KeyScheme::Secp256k1 => {
+            deps.api
+                .secp256k1_verify(request_hash, &signature, pub_key.as_ref())?
+        }
+        KeyScheme::Ed25519 => {
+            deps.api
+                .ed25519_verify(request_hash, &signature, pub_key.as_ref())?
+        }
+    };
 
     if verification_result {
         Ok(())
@@ -237,16 +854,77 @@ pub fn set_owner(deps: DepsMut, _env: Env, info: MessageInfo, owner: String) ->
         .add_attribute("value", owner))
 }
 
+/** Util to verify a public key is well formed before it is stored
+ * secp256k1 keys are compressed (33 bytes) or uncompressed (65 bytes), ed25519 keys are 32 bytes long
+ * */
+fn validate_pubkey(base64_pub_key: &str, scheme: KeyScheme) -> Result<(), ContractError> {
+    let pub_key = base64::decode(base64_pub_key).map_err(|_| ContractError::InvalidPubkey {})?;
+    let valid = match scheme {
+        KeyScheme::Secp256k1 => matches!(
+            (pub_key.len(), pub_key.first()),
+            (33, Some(0x02 | 0x03)) | (65, Some(0x04))
+        ),
+        KeyScheme::Ed25519 => pub_key.len() == 32,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(ContractError::InvalidPubkey {})
+    }
+}
+
 /**
- * Sets the new minter public key of the contract. Can only be called by the current owner
+ * A public key can only be registered under one key_id : signatures are counted once per public key (see find_signing_keys),
+ * so a duplicate would let the owner set a threshold that can't be reached
+ * args:
+ *  key_id: key that is being replaced, it can keep its own public key
  * */
-pub fn set_minter(deps: DepsMut, _env: Env, info: MessageInfo, minter: String) -> Result<Response> {
+fn check_pubkey_not_registered(deps: Deps, pubkey: &str, key_id: &str) -> Result<()> {
+    for key in MINTER_KEYS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, key) = key?;
+        if key.pubkey == pubkey && key.key_id != key_id {
+            return Err(anyhow!(ContractError::MinterPubkeyAlreadyRegistered {
+                key_id: key.key_id
+            }));
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Registers the public key as the default key of the keyring, without validity window
+ * */
+fn save_default_key(deps: DepsMut, pubkey: String, scheme: KeyScheme) -> StdResult<()> {
+    MINTER_KEYS.save(
+        deps.storage,
+        DEFAULT_KEY_ID,
+        &MinterKey {
+            key_id: DEFAULT_KEY_ID.to_string(),
+            pubkey,
+            scheme,
+            valid_from: None,
+            valid_until: None,
+        },
+    )
+}
+
+/**
+ * Sets the new minter public key of the contract (default key of the keyring). Can only be called by the current owner
+ * The other keys of the keyring are not modified, the public key can't be one of theirs
+ * */
+pub fn set_minter(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    minter: String,
+    scheme: Option<KeyScheme>,
+) -> Result<Response> {
     is_owner(deps.as_ref(), info.sender)?;
 
-    CONTRACT_INFO.update::<_, StdError>(deps.storage, |mut x| {
-        x.minter = minter.clone();
-        Ok(x)
-    })?;
+    let scheme = scheme.unwrap_or_default();
+    validate_pubkey(&minter, scheme)?;
+    check_pubkey_not_registered(deps.as_ref(), &minter, DEFAULT_KEY_ID)?;
+    save_default_key(deps, minter.clone(), scheme)?;
 
     Ok(Response::new()
         .add_attribute("action", "parameter_update")
@@ -255,92 +933,278 @@ pub fn set_minter(deps: DepsMut, _env: Env, info: MessageInfo, minter: String) -
 }
 
 /**
- * Sets the new terasury fee price of the contract. Can only be called by the current owner
+ * Adds a public key to the minter keyring. Can only be called by the current owner
+ * A key can be added in advance of a rotation (valid_from) and limited in time (valid_until)
+ * Each public key can only be registered once
  * */
-pub fn set_fee_price(
+pub fn add_minter_key(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    price: Uint128,
+    key: MinterKey,
 ) -> Result<Response> {
     is_owner(deps.as_ref(), info.sender)?;
 
+    validate_pubkey(&key.pubkey, key.scheme)?;
+    if MINTER_KEYS.has(deps.storage, &key.key_id) {
+        return Err(anyhow!(ContractError::MinterKeyAlreadyExists {
+            key_id: key.key_id
+        }));
+    }
+    if let (Some(from), Some(until)) = (key.valid_from, key.valid_until) {
+        if until <= from {
+            return Err(anyhow!(ContractError::InvalidKeyWindow {}));
+        }
+    }
+    check_pubkey_not_registered(deps.as_ref(), &key.pubkey, &key.key_id)?;
+    MINTER_KEYS.save(deps.storage, &key.key_id, &key)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_minter_key")
+        .add_attribute("key_id", key.key_id))
+}
+
+/**
+ * Removes a public key from the minter keyring, effective immediately. Can only be called by the current owner
+ * The key can't be revoked if the keys left could not reach the threshold anymore (see set_threshold)
+ * */
+pub fn revoke_minter_key(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    key_id: String,
+) -> Result<Response> {
+    is_owner(deps.as_ref(), info.sender)?;
+
+    let key = MINTER_KEYS
+        .may_load(deps.storage, &key_id)?
+        .ok_or_else(|| {
+            anyhow!(ContractError::MinterKeyNotFound {
+                key_id: key_id.clone()
+            })
+        })?;
+    let mut usable_keys = usable_key_count(deps.as_ref(), &env)?;
+    if !key.is_expired(&env.block) {
+        usable_keys -= 1;
+    }
+    let threshold = CONTRACT_INFO.load(deps.storage)?.threshold;
+    if usable_keys < threshold as usize {
+        return Err(anyhow!(ContractError::ThresholdNotReachable {
+            key_id,
+            threshold
+        }));
+    }
+    MINTER_KEYS.remove(deps.storage, &key_id);
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_minter_key")
+        .add_attribute("key_id", key_id))
+}
+
+/**
+ * Publishes the root of the Merkle tree of the NFTs that can be minted with a proof. Can only be called by the current owner
+ * The leaves already claimed stay claimed when the root changes
+ * */
+pub fn set_merkle_root(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    merkle_root: Binary,
+) -> Result<Response> {
+    is_owner(deps.as_ref(), info.sender)?;
+
+    if merkle_root.len() != 32 {
+        return Err(anyhow!(ContractError::InvalidMerkleRoot {}));
+    }
+    MERKLE_ROOT.save(deps.storage, &merkle_root)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "parameter_update")
+        .add_attribute("parameter", "merkle_root")
+        .add_attribute("value", merkle_root.to_base64()))
+}
+
+/**
+ * Sets the number of distinct keys that have to sign a mint request. Can only be called by the current owner
+ * The threshold can't exceed the number of keys that can still sign : expired keys are not counted,
+ * keys that are not valid yet are
+ * */
+pub fn set_threshold(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    threshold: u32,
+) -> Result<Response> {
+    is_owner(deps.as_ref(), info.sender)?;
+
+    let keys = usable_key_count(deps.as_ref(), &env)?;
+    if threshold == 0 || threshold as usize > keys {
+        return Err(anyhow!(ContractError::InvalidThreshold { threshold }));
+    }
     CONTRACT_INFO.update::<_, StdError>(deps.storage, |mut x| {
-        x.fee_price = price;
+        x.threshold = threshold;
         Ok(x)
     })?;
 
     Ok(Response::new()
         .add_attribute("action", "parameter_update")
-        .add_attribute("parameter", "fee_price")
-        .add_attribute("value", price.to_string()))
+        .add_attribute("parameter", "threshold")
+        .add_attribute("value", threshold.to_string()))
 }
 
 /**
- * Sets the new project treasury fee price of the contract. Can only be called by the current owner
+ * Counts the keys of the keyring that are active or will be (not expired)
  * */
-pub fn set_project_price(
+fn usable_key_count(deps: Deps, env: &Env) -> StdResult<usize> {
+    let mut count = 0;
+    for item in MINTER_KEYS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, key) = item?;
+        if !key.is_expired(&env.block) {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/**
+ * Replaces the mint prices and the recipients of the mint fee. Can only be called by the current owner
+ * The shares have to sum to 10000 bps
+ * */
+pub fn set_fee_split(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    price: Uint128,
+    prices: Vec<Coin>,
+    shares: Vec<FeeShare>,
+    dust_recipient: String,
 ) -> Result<Response> {
     is_owner(deps.as_ref(), info.sender)?;
 
+    let fee_split = validate_fee_split(deps.as_ref(), &prices, shares, dust_recipient)?;
     CONTRACT_INFO.update::<_, StdError>(deps.storage, |mut x| {
-        x.project_price = price;
+        x.prices = prices.clone();
+        x.fee_split = fee_split.clone();
         Ok(x)
     })?;
 
     Ok(Response::new()
         .add_attribute("action", "parameter_update")
-        .add_attribute("parameter", "project_fee_price")
-        .add_attribute("value", price.to_string()))
+        .add_attribute("parameter", "fee_split")
+        .add_attributes(prices.iter().map(|price| ("price", price.to_string())))
+        .add_attributes(
+            fee_split
+                .shares
+                .iter()
+                .map(|share| ("share", format!("{}:{}", share.recipient, share.bps))),
+        )
+        .add_attribute("dust_recipient", fee_split.dust_recipient))
 }
 
 /**
- * Sets a new treasury address to deposit the fee. Can only be called by the current owner
+ * Replaces the cw20 tokens accepted to pay the mint fee, with their price. Can only be called by the current owner
+ * The paid tokens are split like the native fee (see set_fee_split)
  * */
-pub fn set_treasury(
+pub fn set_cw20_prices(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    treasury: String,
+    prices: Vec<Cw20Coin>,
 ) -> Result<Response> {
     is_owner(deps.as_ref(), info.sender)?;
-    let treasury = deps.api.addr_validate(&treasury)?;
+
+    let cw20_prices = validate_cw20_prices(deps.as_ref(), prices)?;
     CONTRACT_INFO.update::<_, StdError>(deps.storage, |mut x| {
-        x.treasury = treasury.clone();
+        x.cw20_prices = cw20_prices.clone();
         Ok(x)
     })?;
 
     Ok(Response::new()
         .add_attribute("action", "parameter_update")
-        .add_attribute("parameter", "treasury")
-        .add_attribute("value", treasury.to_string()))
+        .add_attribute("parameter", "cw20_prices")
+        .add_attributes(
+            cw20_prices
+                .iter()
+                .map(|price| ("price", format!("{}{}", price.amount, price.address))),
+        ))
+}
+
+/** Util to validate the cw20 tokens accepted to pay the mint fee, a token has only one price
+ * */
+fn validate_cw20_prices(deps: Deps, prices: Vec<Cw20Coin>) -> Result<Vec<Cw20Coin>> {
+    let mut cw20_prices: Vec<Cw20Coin> = vec![];
+    for price in prices {
+        let address = deps.api.addr_validate(&price.address)?.to_string();
+        if cw20_prices.iter().any(|other| other.address == address) {
+            return Err(anyhow!(ContractError::DuplicateFeeDenom { denom: address }));
+        }
+        cw20_prices.push(Cw20Coin {
+            address,
+            amount: price.amount,
+        });
+    }
+    Ok(cw20_prices)
 }
 
 /**
- * Sets a new project treasury address to deposit the project fee. Can only be called by the current owner
+ * Replaces the price schedule of the contract. Can only be called by the current owner
+ * Each tier replaces the prices from its starting time until the next tier starts, the base prices apply before the first tier.
+ * The tiers have to start at increasing times, the fee split is the same for all of them
  * */
-pub fn set_project_treasury(
+pub fn set_price_schedule(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    treasury: String,
+    tiers: Vec<PriceTier>,
 ) -> Result<Response> {
     is_owner(deps.as_ref(), info.sender)?;
-    let treasury = deps.api.addr_validate(&treasury)?;
 
+    let tiers = tiers
+        .into_iter()
+        .map(|tier| {
+            Ok(PriceTier {
+                cw20_prices: validate_cw20_prices(deps.as_ref(), tier.cw20_prices)?,
+                ..tier
+            })
+        })
+        .collect::<Result<Vec<PriceTier>>>()?;
+    check_price_schedule(&tiers)?;
     CONTRACT_INFO.update::<_, StdError>(deps.storage, |mut x| {
-        x.project_treasury = treasury.clone();
+        x.price_schedule = tiers.clone();
         Ok(x)
     })?;
 
     Ok(Response::new()
         .add_attribute("action", "parameter_update")
-        .add_attribute("parameter", "project_treasury")
-        .add_attribute("value", treasury.to_string()))
+        .add_attribute("parameter", "price_schedule")
+        .add_attributes(
+            tiers
+                .iter()
+                .map(|tier| ("starting_at", tier.starting_at.seconds().to_string())),
+        ))
+}
+
+/** Util to validate the recipients of the mint fee before they are stored (see check_fee_split)
+ * */
+fn validate_fee_split(
+    deps: Deps,
+    prices: &[Coin],
+    shares: Vec<FeeShare>,
+    dust_recipient: String,
+) -> Result<FeeSplit> {
+    let fee_split = FeeSplit {
+        shares: shares
+            .into_iter()
+            .map(|share| {
+                Ok(FeeShare {
+                    recipient: deps.api.addr_validate(&share.recipient)?.to_string(),
+                    bps: share.bps,
+                })
+            })
+            .collect::<StdResult<Vec<FeeShare>>>()?,
+        dust_recipient: deps.api.addr_validate(&dust_recipient)?.to_string(),
+    };
+    check_fee_split(prices, &fee_split)?;
+    Ok(fee_split)
 }
 
 /**
@@ -369,8 +1233,50 @@ pub fn set_nft_contract(
 }
 
 /**
- * Queries the contract info (fees, nft_contract...). This is actually not available in the current minter version
+ * Queries the contract info (fees, nft_contract...)
+ * Only the default minter key is returned, the whole keyring is available with the MinterKeys query
+ * */
+pub fn contract_info(deps: Deps) -> StdResult<ContractInfoResponse> {
+    let contract_info = CONTRACT_INFO.load(deps.storage)?;
+    Ok(ContractInfoResponse {
+        name: contract_info.name,
+        owner: contract_info.owner.to_string(),
+        nft_contract: contract_info.nft_contract,
+        minter: MINTER_KEYS
+            .may_load(deps.storage, DEFAULT_KEY_ID)?
+            .map(|key| key.pubkey),
+        threshold: contract_info.threshold,
+        prices: contract_info.prices,
+        cw20_prices: contract_info.cw20_prices,
+        price_schedule: contract_info.price_schedule,
+        fee_split: contract_info.fee_split,
+        merkle_root: MERKLE_ROOT.may_load(deps.storage)?,
+    })
+}
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/**
+ * Returns the keys of the minter keyring (active or not) and the number of keys needed to sign a request
+ * Supports pagination
  * */
-pub fn contract_info(deps: Deps) -> StdResult<ContractInfo> {
-    CONTRACT_INFO.load(deps.storage)
+pub fn minter_keys(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<MinterKeysResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let keys: StdResult<Vec<MinterKey>> = MINTER_KEYS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| key.map(|(_, key)| key))
+        .collect();
+
+    Ok(MinterKeysResponse {
+        threshold: CONTRACT_INFO.load(deps.storage)?.threshold,
+        keys: keys?,
+    })
 }
@@ -15,9 +15,78 @@ pub enum ContractError {
     #[error("Contract Not Initialized")]
     ContractNotInitialized {},
 
-    #[error("Fee not paid correctly, required: {required:?}uust, provided {provided:?}uluna")]
-    FeeNotPaidCorrectly { required: u128, provided: u128 },
+    #[error("Fee not paid correctly, required: {required}, provided {provided}")]
+    FeeNotPaidCorrectly { required: String, provided: String },
+
+    #[error("The fee can't be paid in {denom}")]
+    FeeDenomNotAccepted { denom: String },
+
+    #[error("Duplicate price for {denom}")]
+    DuplicateFeeDenom { denom: String },
+
+    #[error("The fee shares sum to {total_bps} bps instead of 10000")]
+    InvalidFeeShares { total_bps: u32 },
+
+    #[error("The tiers of the price schedule must start at increasing times")]
+    InvalidPriceSchedule {},
 
     #[error("Fee not paid")]
     FeeNotPaid {},
+
+    #[error("Mint request for {provided}, this minter mints {expected}")]
+    NftContractMismatch { expected: String, provided: String },
+
+    #[error("Mint request expired")]
+    MintRequestExpired {},
+
+    #[error("Mint request has to expire (at a block height or time)")]
+    MintRequestNeverExpires {},
+
+    #[error("Mint request already used")]
+    MintRequestAlreadyUsed {},
+
+    #[error("Invalid public key for its signature scheme")]
+    InvalidPubkey {},
+
+    #[error("Minter key {key_id} not found")]
+    MinterKeyNotFound { key_id: String },
+
+    #[error("Minter key {key_id} already exists")]
+    MinterKeyAlreadyExists { key_id: String },
+
+    #[error("This public key is already registered as minter key {key_id}")]
+    MinterPubkeyAlreadyRegistered { key_id: String },
+
+    #[error("Minter key {key_id} is not valid at this time")]
+    MinterKeyInactive { key_id: String },
+
+    #[error("A minter key can't be valid until before it is valid from")]
+    InvalidKeyWindow {},
+
+    #[error("Mint request signed by {provided} keys, {threshold} needed")]
+    NotEnoughSignatures { threshold: u32, provided: u32 },
+
+    #[error("Invalid threshold {threshold}, it has to be between 1 and the number of keys that are not expired")]
+    InvalidThreshold { threshold: u32 },
+
+    #[error("Minter key {key_id} can't be revoked, fewer keys than the threshold ({threshold}) would be left")]
+    ThresholdNotReachable { key_id: String, threshold: u32 },
+
+    #[error("No Merkle root was published")]
+    MerkleRootNotSet {},
+
+    #[error("A Merkle root is a 32 bytes sha256 hash")]
+    InvalidMerkleRoot {},
+
+    #[error("Invalid Merkle proof")]
+    InvalidProof {},
+
+    #[error("Leaf already claimed")]
+    LeafAlreadyClaimed {},
+
+    #[error("Cannot migrate a {found} contract to {expected}")]
+    WrongContract { expected: String, found: String },
+
+    #[error("Cannot migrate from version {stored} to the older version {current}")]
+    CannotDowngrade { stored: String, current: String },
 }
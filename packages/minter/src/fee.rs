@@ -0,0 +1,191 @@
+use cosmwasm_std::{Coin, Timestamp, Uint128};
+
+use crate::error::ContractError;
+use crate::state::{ContractInfo, FeeSplit, PriceTier};
+
+/**
+ * The shares of a fee split are expressed in basis points and have to sum to 100%
+ * */
+pub const TOTAL_BPS: u32 = 10_000;
+
+/**
+ * Verifies a denom has only one price
+ * */
+pub fn check_prices(prices: &[Coin]) -> Result<(), ContractError> {
+    for (i, price) in prices.iter().enumerate() {
+        if prices[..i].iter().any(|other| other.denom == price.denom) {
+            return Err(ContractError::DuplicateFeeDenom {
+                denom: price.denom.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Verifies the mint prices and the fee split can be used :
+ * a denom has only one price and the shares sum to TOTAL_BPS
+ * */
+pub fn check_fee_split(prices: &[Coin], fee_split: &FeeSplit) -> Result<(), ContractError> {
+    check_prices(prices)?;
+    let total_bps: u32 = fee_split.shares.iter().map(|share| share.bps as u32).sum();
+    if total_bps != TOTAL_BPS {
+        return Err(ContractError::InvalidFeeShares { total_bps });
+    }
+    Ok(())
+}
+
+/**
+ * Verifies the tiers of a price schedule start at increasing times and have one price per denom
+ * */
+pub fn check_price_schedule(tiers: &[PriceTier]) -> Result<(), ContractError> {
+    if tiers
+        .windows(2)
+        .any(|tiers| tiers[1].starting_at <= tiers[0].starting_at)
+    {
+        return Err(ContractError::InvalidPriceSchedule {});
+    }
+    for tier in tiers {
+        check_prices(&tier.prices)?;
+    }
+    Ok(())
+}
+
+/**
+ * Returns the prices in effect at the given time, and the tier that follows them if any
+ * Before the first tier of the schedule starts, the base prices of the contract apply
+ * */
+pub fn price_tiers(
+    contract_info: &ContractInfo,
+    time: Timestamp,
+) -> (PriceTier, Option<PriceTier>) {
+    let schedule = &contract_info.price_schedule;
+    let started = schedule
+        .iter()
+        .take_while(|tier| tier.starting_at <= time)
+        .count();
+    let current = match started {
+        0 => PriceTier {
+            starting_at: Timestamp::from_seconds(0),
+            prices: contract_info.prices.clone(),
+            cw20_prices: contract_info.cw20_prices.clone(),
+        },
+        started => schedule[started - 1].clone(),
+    };
+    (current, schedule.get(started).cloned())
+}
+
+/**
+ * Splits a paid amount between the recipients, proportionally to their shares
+ * Each part is rounded down, the rounding dust goes to the dust recipient.
+ * A recipient listed several times receives a single amount, recipients with nothing to receive are omitted
+ * */
+pub fn split_fee(fee_split: &FeeSplit, amount: Uint128) -> Vec<(String, Uint128)> {
+    let mut amounts: Vec<(String, Uint128)> = vec![];
+    let mut add = |recipient: &str, part: Uint128| match amounts
+        .iter_mut()
+        .find(|(address, _)| address == recipient)
+    {
+        Some((_, total)) => *total += part,
+        None => amounts.push((recipient.to_string(), part)),
+    };
+
+    let mut distributed = Uint128::zero();
+    for share in &fee_split.shares {
+        let part = amount.multiply_ratio(share.bps as u128, TOTAL_BPS as u128);
+        distributed += part;
+        add(&share.recipient, part);
+    }
+    add(&fee_split.dust_recipient, amount - distributed);
+
+    amounts.retain(|(_, amount)| !amount.is_zero());
+    amounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::FeeShare;
+    use cosmwasm_std::coin;
+
+    fn fee_split(shares: &[(&str, u16)], dust_recipient: &str) -> FeeSplit {
+        FeeSplit {
+            shares: shares
+                .iter()
+                .map(|(recipient, bps)| FeeShare {
+                    recipient: recipient.to_string(),
+                    bps: *bps,
+                })
+                .collect(),
+            dust_recipient: dust_recipient.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_split_fee() {
+        let three_parties = fee_split(&[("artist", 3333), ("dao", 3333), ("labs", 3334)], "dao");
+        assert_eq!(
+            split_fee(&three_parties, Uint128::from(100u128)),
+            vec![
+                ("artist".to_string(), Uint128::from(33u128)),
+                ("dao".to_string(), Uint128::from(34u128)),
+                ("labs".to_string(), Uint128::from(33u128)),
+            ]
+        );
+        // The parts always sum to the paid amount
+        let total: Uint128 = split_fee(&three_parties, Uint128::from(1_000_001u128))
+            .into_iter()
+            .map(|(_, amount)| amount)
+            .sum();
+        assert_eq!(total, Uint128::from(1_000_001u128));
+
+        // The dust recipient doesn't need a share, empty parts are omitted
+        let one_party = fee_split(&[("artist", 10000), ("dao", 0)], "labs");
+        assert_eq!(
+            split_fee(&one_party, Uint128::from(7u128)),
+            vec![("artist".to_string(), Uint128::from(7u128))]
+        );
+        assert_eq!(split_fee(&one_party, Uint128::zero()), vec![]);
+    }
+
+    #[test]
+    fn test_check_fee_split() {
+        let split = fee_split(&[("artist", 2500), ("dao", 7500)], "dao");
+        check_fee_split(&[coin(10, "uluna"), coin(3, "ibc/usdc")], &split).unwrap();
+
+        assert_eq!(
+            check_fee_split(&[coin(10, "uluna"), coin(3, "uluna")], &split).unwrap_err(),
+            ContractError::DuplicateFeeDenom {
+                denom: "uluna".to_string()
+            }
+        );
+        assert_eq!(
+            check_fee_split(&[], &fee_split(&[("artist", 2500)], "dao")).unwrap_err(),
+            ContractError::InvalidFeeShares { total_bps: 2500 }
+        );
+    }
+
+    #[test]
+    fn test_check_price_schedule() {
+        let tier = |starting_at: u64, prices: Vec<Coin>| PriceTier {
+            starting_at: Timestamp::from_seconds(starting_at),
+            prices,
+            cw20_prices: vec![],
+        };
+        check_price_schedule(&[tier(10, vec![]), tier(20, vec![coin(2, "uluna")])]).unwrap();
+
+        // Two tiers can't start at the same time
+        assert_eq!(
+            check_price_schedule(&[tier(10, vec![]), tier(10, vec![coin(2, "uluna")])])
+                .unwrap_err(),
+            ContractError::InvalidPriceSchedule {}
+        );
+        assert_eq!(
+            check_price_schedule(&[tier(10, vec![coin(2, "uluna"), coin(3, "uluna")])])
+                .unwrap_err(),
+            ContractError::DuplicateFeeDenom {
+                denom: "uluna".to_string()
+            }
+        );
+    }
+}
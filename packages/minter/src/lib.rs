@@ -1,4 +1,7 @@
 pub mod contract;
 pub mod error;
+pub mod fee;
+pub mod merkle;
 pub mod msg;
+pub mod signing;
 pub mod state;
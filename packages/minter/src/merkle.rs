@@ -0,0 +1,128 @@
+use cosmwasm_std::{Binary, StdResult};
+use cw721_base::MintMsg;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::signing::canonical_json;
+
+/**
+ * Leaf of the Merkle tree published by the owner, one leaf per NFT that can be minted
+ * The extension is committed through its hash, so that leaves stay small
+ * */
+#[derive(Serialize)]
+struct MerkleLeaf<'a> {
+    token_id: &'a str,
+    owner: &'a str,
+    token_uri: &'a Option<String>,
+    extension_hash: Binary,
+}
+
+/**
+ * Returns the hash of the leaf corresponding to a mint message :
+ * sha256 of the canonical json of {token_id, owner, token_uri, extension_hash}
+ * where extension_hash is the sha256 of the canonical json of the extension (see canonical_json)
+ * */
+pub fn merkle_leaf<T: Serialize>(mint_msg: &MintMsg<T>) -> StdResult<Vec<u8>> {
+    let leaf = MerkleLeaf {
+        token_id: &mint_msg.token_id,
+        owner: &mint_msg.owner,
+        token_uri: &mint_msg.token_uri,
+        extension_hash: Binary::from(extension_hash(&mint_msg.extension)?),
+    };
+    Ok(Sha256::digest(&canonical_json(&leaf)?).to_vec())
+}
+
+/**
+ * Returns the sha256 hash of the canonical json of an extension
+ * The escrow hashes the extension of the deposited tokens the same way (see its DepositSnapshot query)
+ * */
+pub fn extension_hash<T: Serialize>(extension: &T) -> StdResult<Vec<u8>> {
+    Ok(Sha256::digest(&canonical_json(extension)?).to_vec())
+}
+
+/**
+ * Verifies the leaf is included in the tree with the given root
+ * Each node is the sha256 hash of its two children, sorted (smallest first), so the proof only lists the sibling hashes
+ * */
+pub fn verify_proof(root: &[u8], leaf: &[u8], proof: &[Binary]) -> bool {
+    let computed = proof.iter().fold(leaf.to_vec(), |hash, sibling| {
+        let (first, second) = if hash.as_slice() <= sibling.as_slice() {
+            (hash.as_slice(), sibling.as_slice())
+        } else {
+            (sibling.as_slice(), hash.as_slice())
+        };
+        Sha256::new().chain(first).chain(second).finalize().to_vec()
+    });
+    computed == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    struct Extension {
+        name: String,
+    }
+
+    fn mint_msg(
+        token_id: &str,
+        token_uri: Option<&str>,
+        name: Option<&str>,
+    ) -> MintMsg<Option<Extension>> {
+        MintMsg {
+            token_id: token_id.to_string(),
+            owner: format!("terra1owner{}", token_id),
+            token_uri: token_uri.map(|uri| uri.to_string()),
+            extension: name.map(|name| Extension {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    fn binary(base64_hash: &str) -> Binary {
+        Binary::from_base64(base64_hash).unwrap()
+    }
+
+    #[test]
+    fn test_merkle_proof() {
+        // Tree over 3 leaves : root = H(H(leaf1, leaf2), leaf3)
+        let leaf1 = merkle_leaf(&mint_msg("1", Some("ipfs://1"), Some("one"))).unwrap();
+        let leaf2 = merkle_leaf(&mint_msg("2", None, None)).unwrap();
+        let leaf3 = merkle_leaf(&mint_msg("3", Some("ipfs://3"), Some("three"))).unwrap();
+        assert_eq!(
+            leaf1,
+            binary("OHX/8/oNQoxIv74F0eyMDzYtJHoGYxP7zH2N7S34Grc=").to_vec()
+        );
+        assert_eq!(
+            leaf2,
+            binary("0pAImLvlvSwllSFxVHQ9mZlOUlvfEVKikenhdvf8Uws=").to_vec()
+        );
+        assert_eq!(
+            leaf3,
+            binary("0tG6T0i8IF/8QOz6PbXKn5f/uDMyHSpRB0Zs5I+sQKY=").to_vec()
+        );
+
+        let root = binary("qeoI9ED0lnjhNwGxtgNOU6q4JdlK6Wp6ADje5RFS0qk=");
+        assert!(verify_proof(
+            &root,
+            &leaf1,
+            &[Binary::from(leaf2.clone()), Binary::from(leaf3.clone())]
+        ));
+        assert!(verify_proof(
+            &root,
+            &leaf3,
+            &[binary("vYTKBmBUp06Yi6Kc14NST+UxA0XyWWis9rJbiI6F158=")]
+        ));
+
+        // A modified leaf or a wrong proof is refused
+        let modified = merkle_leaf(&mint_msg("1", Some("ipfs://1"), Some("other"))).unwrap();
+        assert!(!verify_proof(
+            &root,
+            &modified,
+            &[Binary::from(leaf2.clone()), Binary::from(leaf3.clone())]
+        ));
+        assert!(!verify_proof(&root, &leaf1, &[Binary::from(leaf2)]));
+    }
+}
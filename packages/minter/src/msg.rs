@@ -1,8 +1,14 @@
-use cosmwasm_std::{to_binary, Binary, Coin, CosmosMsg, StdError, StdResult, Uint128, WasmMsg};
+use cosmwasm_std::{
+    to_binary, Binary, Coin, CosmosMsg, StdError, StdResult, Timestamp, Uint64, WasmMsg,
+};
+use cw20::{Cw20Coin, Cw20ReceiveMsg};
 use cw721_base::MintMsg;
+pub use cw_utils::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::state::{FeeShare, FeeSplit, KeyScheme, MinterKey, PriceTier};
+
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct MigrateMsg {}
 
@@ -10,11 +16,18 @@ pub struct MigrateMsg {}
 pub struct InstantiateMsg {
     pub name: String,
     pub owner: Option<String>,
+    /// Public key of the authority, registered as the default key of the keyring
     pub minter: String,
-    pub fee_price: Uint128,
-    pub treasury: Option<String>,
-    pub project_price: Uint128,
-    pub project_treasury: String,
+    /// Signature scheme of the minter key. Default : secp256k1
+    pub minter_scheme: Option<KeyScheme>,
+    /// Number of distinct keys that have to sign a mint request. Default : 1
+    pub threshold: Option<u32>,
+    /// Total mint price in each of the accepted denoms. The mint is free if empty
+    pub prices: Vec<Coin>,
+    /// Recipients of the mint price, their shares have to sum to 10000 bps
+    pub fee_shares: Vec<FeeShare>,
+    /// Receives the rounding dust of the split. Default : initialization sender
+    pub dust_recipient: Option<String>,
 }
 
 pub fn is_valid_name(name: &str) -> bool {
@@ -32,6 +45,12 @@ impl InstantiateMsg {
                 "Name is not in the expected format (3-50 UTF-8 bytes)",
             ));
         }
+        // Only the default key exists at instantiation
+        if !matches!(self.threshold, None | Some(1)) {
+            return Err(StdError::generic_err(
+                "The threshold can't exceed the number of keys (1 at instantiation)",
+            ));
+        }
         Ok(())
     }
 }
@@ -41,7 +60,16 @@ impl InstantiateMsg {
 pub enum ExecuteMsg<T: Clone> {
     Mint {
         mint_request: MintRequest<T>,
-        signature: String,
+        signatures: Vec<MintSignature>,
+    },
+    /// Mints an NFT of the Merkle tree published by the owner, without signature
+    MintWithProof {
+        mint_msg: MintMsg<T>,
+        /// Hashes of the siblings of the leaf, from the leaf to the root
+        proof: Vec<Binary>,
+    },
+    SetMerkleRoot {
+        merkle_root: Binary,
     },
     SetOwner {
         owner: String,
@@ -49,27 +77,74 @@ pub enum ExecuteMsg<T: Clone> {
     SetNftContract {
         nft_contract: String,
     },
+    /// Replaces the default key of the keyring
     SetMinter {
         minter: String,
+        scheme: Option<KeyScheme>,
     },
-    SetFeePrice {
-        price: Uint128,
+    AddMinterKey {
+        key_id: String,
+        pubkey: String,
+        /// Default : secp256k1
+        scheme: Option<KeyScheme>,
+        valid_from: Option<Timestamp>,
+        valid_until: Option<Timestamp>,
     },
-    SetProjectFeePrice {
-        price: Uint128,
+    RevokeMinterKey {
+        key_id: String,
     },
-    SetTreasury {
-        treasury: String,
+    SetThreshold {
+        threshold: u32,
     },
-    SetProjectTreasury {
-        treasury: String,
+    /// Replaces the mint prices and their recipients
+    SetFeeSplit {
+        prices: Vec<Coin>,
+        shares: Vec<FeeShare>,
+        dust_recipient: String,
+    },
+    /// Replaces the cw20 tokens accepted to pay the mint, with their price
+    SetCw20Prices {
+        prices: Vec<Cw20Coin>,
+    },
+    /// Replaces the tiers of prices applied from their starting time
+    SetPriceSchedule {
+        tiers: Vec<PriceTier>,
+    },
+    /// Mints an NFT paid with an accepted cw20 token, the cw20 message wraps a ReceiveMsg
+    Receive(Cw20ReceiveMsg),
+}
+
+/**
+ * Messages sent by the cw20 tokens, along with the payment
+ * */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg<T> {
+    Mint {
+        mint_request: MintRequest<T>,
+        signatures: Vec<MintSignature>,
     },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum QueryMsg {
+pub enum QueryMsg<T> {
+    ContractInfo {},
     FeePrice {},
+    MinterKeys {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    MerkleRoot {},
+    /// Runs the checks of a Mint message without executing it
+    VerifyMintRequest {
+        mint_request: MintRequest<T>,
+        signatures: Vec<MintSignature>,
+        /// Funds that would be sent with the Mint message
+        funds: Vec<Coin>,
+        /// Cw20 tokens that would be sent with the Mint message (see ReceiveMsg), instead of funds
+        cw20_funds: Option<Cw20Coin>,
+    },
 }
 
 pub fn into_binary<M: Serialize>(msg: M) -> StdResult<Binary> {
@@ -95,11 +170,105 @@ pub fn into_cosmos_msg<M: Serialize, T: Into<String>>(
 pub struct MintRequest<T> {
     pub mint_msg: MintMsg<T>,
     pub nft_contract: String,
+    /// Makes every signed request unique, a request can only be used once.
+    /// Encoded as a string, so that signers written in javascript don't lose precision
+    pub nonce: Uint64,
+    /// The request can't be used after this block height or time. Requests that never expire are refused
+    pub expires_at: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MintSignature {
+    /// Key of the keyring that signed the request. If not specified, every active key is tried
+    pub key_id: Option<String>,
+    pub signature: String,
+    /// Address of the wallet that produced an ADR-036 (signArbitrary) signature of the signing bytes.
+    /// If not specified, the signature is made on the sha256 hash of the signing bytes
+    pub adr036_signer: Option<String>,
+}
+
+/**
+ * Message signed by the minter authority.
+ * The mint request is bound to a chain and to a minter contract,
+ * so that a signature can't be used on another chain or on another minter sharing the same authority
+ * */
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SigningPayload<'a, T> {
+    pub chain_id: &'a str,
+    pub minter_contract: &'a str,
+    pub mint_request: &'a MintRequest<T>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ContractInfoResponse {
+    pub name: String,
+    pub owner: String,
+    pub nft_contract: Option<String>,
+    /// Public key of the default key of the keyring (see the MinterKeys query for all the keys)
+    pub minter: Option<String>,
+    pub threshold: u32,
+    pub prices: Vec<Coin>,
+    pub cw20_prices: Vec<Cw20Coin>,
+    pub price_schedule: Vec<PriceTier>,
+    pub fee_split: FeeSplit,
+    pub merkle_root: Option<Binary>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct FeeResponse {
-    pub fee_price: Uint128,
-    pub project_price: Uint128,
+    /// Prices in effect at the current block time
+    pub current_tier: PriceTier,
+    /// Prices that will apply next, if the price schedule has more tiers
+    pub next_tier: Option<PriceTier>,
+    pub fee_split: FeeSplit,
+}
+
+/**
+ * Checks run before minting a signed request, in this order
+ * */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MintCheck {
+    /// The nft contract of the minter is set
+    ContractInitialized,
+    /// The request targets the nft contract of the minter
+    NftContract,
+    /// The fee is paid
+    Fee,
+    /// The request is not expired
+    Expiration,
+    /// The request was not used yet
+    AlreadyUsed,
+    /// All the signatures are valid signatures of active keys
+    Signature,
+    /// Enough distinct keys signed the request
+    Threshold,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct VerifyMintRequestResponse {
+    pub valid: bool,
+    /// First check that failed
+    pub failed_check: Option<MintCheck>,
+    /// Error the Mint message would return
+    pub error: Option<String>,
+    /// Keys that signed the request, when it is valid
+    pub key_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MerkleRootResponse {
+    pub merkle_root: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MinterKeysResponse {
+    pub threshold: u32,
+    pub keys: Vec<MinterKey>,
 }
@@ -0,0 +1,188 @@
+use cosmwasm_std::{from_slice, to_vec, Binary, Empty, StdResult};
+use migration_utils::json::JsonValue;
+use serde::Serialize;
+
+use crate::msg::{MintRequest, SigningPayload};
+
+/**
+ * Returns the exact bytes the minter authority signs for a mint request.
+ * The signed message is the SigningPayload (chain_id, minter_contract, mint_request) encoded as canonical json :
+ *  - object keys are sorted (byte order) at every level
+ *  - no whitespace
+ *  - None values are encoded as null, they are never omitted
+ *  - numbers are integers, written without leading zeros
+ *  - strings are utf-8, only quotes, backslashes and control characters are escaped
+ *
+ * The contract verifies the signature against the sha256 hash of these bytes.
+ * See testdata/signing_vectors.json to check a signer against this encoding
+ * */
+pub fn signing_bytes<T: Serialize>(
+    chain_id: &str,
+    minter_contract: &str,
+    mint_request: &MintRequest<T>,
+) -> StdResult<Vec<u8>> {
+    let payload = SigningPayload {
+        chain_id,
+        minter_contract,
+        mint_request,
+    };
+    canonical_json(&payload)
+}
+
+/**
+ * Returns the amino sign doc a wallet signs with ADR-036 (signArbitrary in Keplr and Station)
+ * The doc wraps the data in a MsgSignData from the signer address, with empty chain_id, fee, memo and sequence.
+ * Like any amino sign doc, it is encoded as json with sorted keys. The wallet signs its sha256 hash
+ * args:
+ *  signer: bech32 address of the wallet that signs
+ *  data: signed data, the signing_bytes of the mint request
+ * */
+pub fn adr036_sign_doc(signer: &str, data: &[u8]) -> StdResult<Vec<u8>> {
+    let sign_doc = AminoSignDoc {
+        account_number: "0",
+        chain_id: "",
+        fee: AminoFee {
+            amount: vec![],
+            gas: "0",
+        },
+        memo: "",
+        msgs: vec![AminoMsg {
+            r#type: "sign/MsgSignData",
+            value: MsgSignData {
+                data: Binary::from(data),
+                signer,
+            },
+        }],
+        sequence: "0",
+    };
+    canonical_json(&sign_doc)
+}
+
+#[derive(Serialize)]
+struct AminoSignDoc<'a> {
+    account_number: &'a str,
+    chain_id: &'a str,
+    fee: AminoFee<'a>,
+    memo: &'a str,
+    msgs: Vec<AminoMsg<'a>>,
+    sequence: &'a str,
+}
+
+#[derive(Serialize)]
+struct AminoFee<'a> {
+    amount: Vec<Empty>,
+    gas: &'a str,
+}
+
+#[derive(Serialize)]
+struct AminoMsg<'a> {
+    r#type: &'a str,
+    value: MsgSignData<'a>,
+}
+
+#[derive(Serialize)]
+struct MsgSignData<'a> {
+    data: Binary,
+    signer: &'a str,
+}
+
+/**
+ * Encodes a message as canonical json (see signing_bytes)
+ * The message is first encoded by serde, then decoded as a generic json value (JsonValue) and written again with sorted keys
+ * */
+pub fn canonical_json<T: Serialize>(message: &T) -> StdResult<Vec<u8>> {
+    let value: JsonValue = from_slice(&to_vec(message)?)?;
+    Ok(value.to_canonical_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cw721_base::MintMsg;
+    use serde::Deserialize;
+    use sha2::{Digest, Sha256};
+
+    use crate::msg::Expiration;
+    use cosmwasm_std::Uint64;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    struct Trait {
+        trait_type: String,
+        value: String,
+        display_type: Option<String>,
+    }
+
+    /**
+     * Extension with its fields declared out of order, to check they are sorted
+     * */
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    struct Metadata {
+        name: Option<String>,
+        description: Option<String>,
+        attributes: Option<Vec<Trait>>,
+        edition: Option<u64>,
+    }
+
+    #[derive(Deserialize)]
+    struct TestVector {
+        chain_id: String,
+        minter_contract: String,
+        mint_request: MintRequest<Option<Metadata>>,
+        signing_bytes: String,
+        sha256: String,
+    }
+
+    #[test]
+    fn test_signing_vectors() {
+        let vectors: Vec<TestVector> =
+            from_slice(include_bytes!("../testdata/signing_vectors.json")).unwrap();
+        assert!(!vectors.is_empty());
+
+        for vector in vectors {
+            let bytes = signing_bytes(
+                &vector.chain_id,
+                &vector.minter_contract,
+                &vector.mint_request,
+            )
+            .unwrap();
+            assert_eq!(
+                String::from_utf8(bytes.clone()).unwrap(),
+                vector.signing_bytes
+            );
+            assert_eq!(base64::encode(Sha256::digest(&bytes)), vector.sha256);
+        }
+    }
+
+    #[test]
+    fn test_canonical_json() {
+        let mint_request = MintRequest {
+            mint_msg: MintMsg {
+                token_id: "1".to_string(),
+                owner: "owner".to_string(),
+                token_uri: None,
+                extension: Metadata {
+                    name: Some("quote \" backslash \\ newline \n".to_string()),
+                    description: None,
+                    attributes: Some(vec![]),
+                    edition: Some(0),
+                },
+            },
+            nft_contract: "nft".to_string(),
+            nonce: Uint64::MAX,
+            expires_at: Expiration::AtHeight(4200000),
+        };
+        assert_eq!(
+            String::from_utf8(signing_bytes("chain", "minter", &mint_request).unwrap()).unwrap(),
+            r#"{"chain_id":"chain","mint_request":{"expires_at":{"at_height":4200000},"mint_msg":{"extension":{"attributes":[],"description":null,"edition":0,"name":"quote \" backslash \\ newline \n"},"owner":"owner","token_id":"1","token_uri":null},"nft_contract":"nft","nonce":"18446744073709551615"},"minter_contract":"minter"}"#
+        );
+    }
+
+    #[test]
+    fn test_adr036_sign_doc() {
+        assert_eq!(
+            String::from_utf8(adr036_sign_doc("terra1signer", br#"{"chain_id":"chain"}"#).unwrap())
+                .unwrap(),
+            r#"{"account_number":"0","chain_id":"","fee":{"amount":[],"gas":"0"},"memo":"","msgs":[{"type":"sign/MsgSignData","value":{"data":"eyJjaGFpbl9pZCI6ImNoYWluIn0=","signer":"terra1signer"}}],"sequence":"0"}"#
+        );
+    }
+}
@@ -1,12 +1,79 @@
 use crate::error::ContractError;
-use cosmwasm_std::{Addr, Deps, Uint128};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Binary, BlockInfo, Coin, Deps, Empty, Timestamp, Uint128};
+use cw20::Cw20Coin;
+use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct ContractInfo {
+    pub name: String,
+    pub owner: Addr,
+    pub nft_contract: Option<String>,
+    /// Number of distinct keys of the keyring that have to sign a mint request
+    pub threshold: u32,
+    /// Total mint price in each of the accepted denoms
+    pub prices: Vec<Coin>,
+    /// Cw20 tokens accepted to pay the mint, with the total price in each token
+    pub cw20_prices: Vec<Cw20Coin>,
+    /// Tiers replacing the prices above from their starting time, sorted by starting time (see price_tiers)
+    pub price_schedule: Vec<PriceTier>,
+    /// How the paid price is shared between the recipients
+    pub fee_split: FeeSplit,
+}
+
+/**
+ * Prices applied from starting_at, until the next tier of the price schedule starts
+ * */
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct PriceTier {
+    pub starting_at: Timestamp,
+    /// Total mint price in each of the accepted denoms
+    pub prices: Vec<Coin>,
+    /// Total mint price in each of the accepted cw20 tokens
+    pub cw20_prices: Vec<Cw20Coin>,
+}
+
+impl PriceTier {
+    pub fn price(&self, denom: &str) -> Option<&Coin> {
+        self.prices.iter().find(|price| price.denom == denom)
+    }
+
+    pub fn cw20_price(&self, token: &str) -> Option<&Cw20Coin> {
+        self.cw20_prices.iter().find(|price| price.address == token)
+    }
+}
+
+/**
+ * Share of the mint price received by an address, in basis points (10000 is the whole price)
+ * */
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct FeeShare {
+    pub recipient: String,
+    pub bps: u16,
+}
+
+/**
+ * Recipients of the mint price. The shares sum to 10000 bps, the rounding dust goes to the dust_recipient
+ * (see split_fee)
+ * */
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct FeeSplit {
+    pub shares: Vec<FeeShare>,
+    pub dust_recipient: String,
+}
+
+/**
+ * Contract information of the minters deployed before the authority keyring
+ * The minter field holds the only public key of the authority
+ * */
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct LegacyContractInfo {
     pub name: String,
     pub owner: Addr,
     pub nft_contract: Option<String>,
@@ -17,7 +84,84 @@ pub struct ContractInfo {
     pub project_treasury: Addr,
 }
 
-pub const CONTRACT_INFO: Item<ContractInfo> = Item::new("contract_info");
+/**
+ * Signature scheme of an authority key
+ * */
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyScheme {
+    Secp256k1,
+    Ed25519,
+}
+
+impl Default for KeyScheme {
+    fn default() -> Self {
+        KeyScheme::Secp256k1
+    }
+}
+
+/**
+ * Public key of an authority allowed to sign mint requests
+ * The key can only be used from valid_from and until valid_until (when they are set)
+ * */
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct MinterKey {
+    pub key_id: String,
+    /// base64 encoded public key
+    pub pubkey: String,
+    pub scheme: KeyScheme,
+    pub valid_from: Option<Timestamp>,
+    pub valid_until: Option<Timestamp>,
+}
+
+impl MinterKey {
+    pub fn is_active(&self, block: &BlockInfo) -> bool {
+        self.valid_from.map_or(true, |from| block.time >= from)
+            && self.valid_until.map_or(true, |until| block.time < until)
+    }
+
+    /// An expired key can never be used again, a key that is not valid yet will be
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.valid_until.map_or(false, |until| block.time >= until)
+    }
+}
+
+/**
+ * The contract information moved away from the "contract_info" key, which holds the contract version (cw2)
+ * */
+pub const CONTRACT_INFO: Item<ContractInfo> = Item::new("minter_info");
+
+/**
+ * Contract information of the minters deployed before the contracts were versioned
+ * Only read when migrating them
+ * */
+pub const LEGACY_CONTRACT_INFO: Item<LegacyContractInfo> = Item::new("contract_info");
+
+/**
+ * Keyring of the authorities that can sign mint requests, indexed by key_id
+ * */
+pub const MINTER_KEYS: Map<&str, MinterKey> = Map::new("minter_keys");
+
+/**
+ * Id of the key set at instantiation or with SetMinter
+ * */
+pub const DEFAULT_KEY_ID: &str = "default";
+
+/**
+ * Hashes of the mint requests that were already used to mint
+ * */
+pub const USED_REQUESTS: Map<&[u8], Empty> = Map::new("used_requests");
+
+/**
+ * Root of the Merkle tree of the NFTs that can be minted with a proof (see merkle_leaf)
+ * */
+pub const MERKLE_ROOT: Item<Binary> = Item::new("merkle_root");
+
+/**
+ * Leaves of the Merkle tree that were already minted
+ * */
+pub const CLAIMED_LEAVES: Map<&[u8], Empty> = Map::new("claimed_leaves");
 
 pub fn is_owner(deps: Deps, addr: Addr) -> Result<(), ContractError> {
     if CONTRACT_INFO.load(deps.storage)?.owner == addr {
@@ -0,0 +1,196 @@
+use serde::de::{Deserialize, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/**
+ * Generic json value. Objects are stored in a BTreeMap so that their keys are sorted
+ * Floats are not supported, just like in the rest of cosmwasm
+ *
+ * The value is written as canonical json (see to_canonical_bytes), so that the escrow and the minter
+ * hash the same bytes for the same metadata, whatever the formatting of the json they received
+ * */
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Unsigned(u64),
+    Signed(i64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(BTreeMap<String, JsonValue>),
+}
+
+impl JsonValue {
+    /**
+     * Encodes the value as canonical json :
+     *  - object keys are sorted (byte order) at every level
+     *  - no whitespace
+     *  - numbers are integers, written without leading zeros
+     *  - strings are utf-8, only quotes, backslashes and control characters are escaped
+     * */
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write(&mut bytes);
+        bytes
+    }
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        match self {
+            JsonValue::Null => bytes.extend_from_slice(b"null"),
+            JsonValue::Bool(value) => bytes.extend_from_slice(value.to_string().as_bytes()),
+            JsonValue::Unsigned(value) => bytes.extend_from_slice(value.to_string().as_bytes()),
+            JsonValue::Signed(value) => bytes.extend_from_slice(value.to_string().as_bytes()),
+            JsonValue::String(value) => write_string(value, bytes),
+            JsonValue::Array(values) => {
+                bytes.push(b'[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        bytes.push(b',');
+                    }
+                    value.write(bytes);
+                }
+                bytes.push(b']');
+            }
+            JsonValue::Object(fields) => {
+                bytes.push(b'{');
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        bytes.push(b',');
+                    }
+                    write_string(key, bytes);
+                    bytes.push(b':');
+                    value.write(bytes);
+                }
+                bytes.push(b'}');
+            }
+        }
+    }
+}
+
+/**
+ * Writes a json string, escaped the same way as serde-json-wasm
+ * */
+fn write_string(value: &str, bytes: &mut Vec<u8>) {
+    bytes.push(b'"');
+    for c in value.chars() {
+        match c {
+            '\\' => bytes.extend_from_slice(b"\\\\"),
+            '"' => bytes.extend_from_slice(b"\\\""),
+            '\u{0008}' => bytes.extend_from_slice(b"\\b"),
+            '\u{0009}' => bytes.extend_from_slice(b"\\t"),
+            '\u{000A}' => bytes.extend_from_slice(b"\\n"),
+            '\u{000C}' => bytes.extend_from_slice(b"\\f"),
+            '\u{000D}' => bytes.extend_from_slice(b"\\r"),
+            '\u{0000}'..='\u{001F}' => {
+                bytes.extend_from_slice(format!("\\u{:04X}", c as u32).as_bytes())
+            }
+            _ => {
+                let mut encoded = [0u8; 4];
+                bytes.extend_from_slice(c.encode_utf8(&mut encoded).as_bytes());
+            }
+        }
+    }
+    bytes.push(b'"');
+}
+
+impl<'de> Deserialize<'de> for JsonValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonValueVisitor)
+    }
+}
+
+struct JsonValueVisitor;
+
+impl<'de> Visitor<'de> for JsonValueVisitor {
+    type Value = JsonValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a json value")
+    }
+
+    fn visit_unit<E>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<JsonValue, D::Error> {
+        JsonValue::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<JsonValue, E> {
+        Ok(JsonValue::Bool(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Unsigned(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Signed(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<JsonValue, E> {
+        Ok(JsonValue::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<JsonValue, E> {
+        Ok(JsonValue::String(value))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsonValue, A::Error> {
+        let mut values = vec![];
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(JsonValue::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsonValue, A::Error> {
+        let mut fields = BTreeMap::new();
+        while let Some((JsonKey(key), value)) = map.next_entry::<JsonKey, JsonValue>()? {
+            fields.insert(key, value);
+        }
+        Ok(JsonValue::Object(fields))
+    }
+}
+
+/**
+ * Object key. serde-json-wasm only deserializes map keys with deserialize_str
+ * */
+struct JsonKey(String);
+
+impl<'de> Deserialize<'de> for JsonKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match deserializer.deserialize_str(JsonValueVisitor)? {
+            JsonValue::String(key) => Ok(JsonKey(key)),
+            _ => Err(D::Error::custom("object keys must be strings")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_bytes() {
+        let value: JsonValue = serde_json_wasm::from_slice(
+            br#" { "b" : [ 1, -2, null, true ] , "a" : { "d" : "x", "c" : {} } } "#,
+        )
+        .unwrap();
+        assert_eq!(
+            value.to_canonical_bytes(),
+            br#"{"a":{"c":{},"d":"x"},"b":[1,-2,null,true]}"#.to_vec()
+        );
+
+        // Strings are escaped like serde-json-wasm does
+        let string: String = (0u8..0x80).map(char::from).chain("é€😀".chars()).collect();
+        assert_eq!(
+            JsonValue::String(string.clone()).to_canonical_bytes(),
+            serde_json_wasm::to_vec(&string).unwrap()
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use semver::Version;
+
+/**
+ * Parses a contract version (major.minor.patch, with an optional pre-release such as 1.0.0-beta.1)
+ * Versions are ordered following semver, a pre-release comes before its release
+ * */
+pub fn parse_version(version: &str) -> Result<Version, String> {
+    Version::parse(version).map_err(|_| format!("Invalid version {}", version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert!(parse_version("0.9.0").unwrap() < parse_version("0.10.0").unwrap());
+        assert!(parse_version("1.0.0-beta.2").unwrap() < parse_version("1.0.0").unwrap());
+        assert!(parse_version("1.0.0-beta.2").unwrap() > parse_version("1.0.0-beta.1").unwrap());
+        assert_eq!(
+            parse_version("1.0").unwrap_err(),
+            "Invalid version 1.0".to_string()
+        );
+    }
+}